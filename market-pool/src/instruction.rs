@@ -0,0 +1,140 @@
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    system_program,
+};
+use spl_associated_token_account_client::address::get_associated_token_address_with_program_id;
+
+pub const POOL_PDA_SEED: &[u8] = b"pool";
+pub const LP_MINT_PDA_SEED: &[u8] = b"lp";
+
+pub const LP_MINT_DECIMALS: u8 = 9;
+
+pub const INITIATE_POOL_TAG: u8 = 0;
+// same tag as `contract::market_pool::MARKET_POOL_DEPOSIT_TAG`
+pub const DEPOSIT_TAG: u8 = 1;
+
+/// The pool does not care which side a mint is on, so the pair is ordered by
+/// key before deriving its address.
+pub fn sort_mints<'a>(mint_a: &'a Pubkey, mint_b: &'a Pubkey) -> (&'a Pubkey, &'a Pubkey) {
+    if mint_a.to_bytes() <= mint_b.to_bytes() {
+        (mint_a, mint_b)
+    } else {
+        (mint_b, mint_a)
+    }
+}
+
+// pool: [POOL_PDA_SEED, mint_low, mint_high]
+pub fn find_pool_address(program_id: &Pubkey, mint_a: &Pubkey, mint_b: &Pubkey) -> (Pubkey, u8) {
+    let (mint_low, mint_high) = sort_mints(mint_a, mint_b);
+    Pubkey::find_program_address(
+        &[POOL_PDA_SEED, mint_low.as_ref(), mint_high.as_ref()],
+        program_id,
+    )
+}
+
+// lp mint: [LP_MINT_PDA_SEED, pool]
+pub fn find_lp_mint_address(program_id: &Pubkey, pool: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[LP_MINT_PDA_SEED, pool.as_ref()], program_id)
+}
+
+/// Creates the pool of a pair of mints: the vault ATAs owned by the pool PDA
+/// and the Token-2022 LP mint the pool PDA is the authority of.
+///
+/// Accounts expected:
+///   0. `[signer, writable]` payer
+///   1. `[]` pool PDA
+///   2. `[]` mint A
+///   3. `[writable]` pool ATA of mint A
+///   4. `[]` token program of mint A
+///   5. `[]` mint B
+///   6. `[writable]` pool ATA of mint B
+///   7. `[]` token program of mint B
+///   8. `[writable]` LP mint PDA
+///   9. `[]` LP token program, Token-2022
+///  10. `[]` associated token account program
+///  11. `[]` system program
+pub fn initiate_pool(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    mint_a: &Pubkey,
+    token_program_a: &Pubkey,
+    mint_b: &Pubkey,
+    token_program_b: &Pubkey,
+) -> Instruction {
+    let (pool, _) = find_pool_address(program_id, mint_a, mint_b);
+    let (lp_mint, _) = find_lp_mint_address(program_id, &pool);
+
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new_readonly(pool, false),
+            AccountMeta::new_readonly(*mint_a, false),
+            AccountMeta::new(
+                get_associated_token_address_with_program_id(&pool, mint_a, token_program_a),
+                false,
+            ),
+            AccountMeta::new_readonly(*token_program_a, false),
+            AccountMeta::new_readonly(*mint_b, false),
+            AccountMeta::new(
+                get_associated_token_address_with_program_id(&pool, mint_b, token_program_b),
+                false,
+            ),
+            AccountMeta::new_readonly(*token_program_b, false),
+            AccountMeta::new(lp_mint, false),
+            AccountMeta::new_readonly(spl_token_2022::id(), false),
+            AccountMeta::new_readonly(spl_associated_token_account::id(), false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: vec![INITIATE_POOL_TAG],
+    }
+}
+
+/// Pool specific accounts of the deposit, appended after the accounts of
+/// `contract::market_pool::deposit`. The depositor LP ATA has to exist.
+///
+/// Accounts expected:
+///   7. `[]` pool PDA
+///   8. `[writable]` pool ATA of the funded mint
+///   9. `[writable]` pool ATA of the offered mint
+///  10. `[writable]` LP mint PDA
+///  11. `[writable]` depositor LP ATA
+///  12. `[]` LP token program, Token-2022
+pub fn deposit_accounts(
+    program_id: &Pubkey,
+    depositor: &Pubkey,
+    funded_mint: &Pubkey,
+    funded_token_program: &Pubkey,
+    offered_mint: &Pubkey,
+    offered_token_program: &Pubkey,
+) -> Vec<AccountMeta> {
+    let (pool, _) = find_pool_address(program_id, funded_mint, offered_mint);
+    let (lp_mint, _) = find_lp_mint_address(program_id, &pool);
+
+    vec![
+        AccountMeta::new_readonly(pool, false),
+        AccountMeta::new(
+            get_associated_token_address_with_program_id(&pool, funded_mint, funded_token_program),
+            false,
+        ),
+        AccountMeta::new(
+            get_associated_token_address_with_program_id(
+                &pool,
+                offered_mint,
+                offered_token_program,
+            ),
+            false,
+        ),
+        AccountMeta::new(lp_mint, false),
+        AccountMeta::new(
+            get_associated_token_address_with_program_id(
+                depositor,
+                &lp_mint,
+                &spl_token_2022::id(),
+            ),
+            false,
+        ),
+        AccountMeta::new_readonly(spl_token_2022::id(), false),
+    ]
+}
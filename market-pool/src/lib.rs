@@ -0,0 +1,16 @@
+//! Minimal constant-product pool used to exercise the market pool deposit of
+//! the contract locally. It only implements what the deposit needs: creating a
+//! pool for a pair of mints and adding liquidity to it, no swaps.
+
+pub mod instruction;
+pub mod processor;
+
+#[cfg(not(feature = "no-entrypoint"))]
+use processor::process_instruction;
+#[cfg(not(feature = "no-entrypoint"))]
+use solana_program::entrypoint;
+
+solana_program::declare_id!("9nKd1bmjRDdTtw2anVYPKqpsn1Q1YqmjQsoy1eZC8FNm");
+
+#[cfg(not(feature = "no-entrypoint"))]
+entrypoint!(process_instruction);
@@ -0,0 +1,405 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+use spl_associated_token_account_client::address::get_associated_token_address_with_program_id;
+use spl_token_2022::{
+    extension::StateWithExtensions,
+    state::{Account, Mint},
+};
+
+use crate::instruction::{
+    find_lp_mint_address, find_pool_address, sort_mints, DEPOSIT_TAG, INITIATE_POOL_TAG,
+    LP_MINT_DECIMALS, LP_MINT_PDA_SEED, POOL_PDA_SEED,
+};
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> Result<(), ProgramError> {
+    let (tag, rest) = instruction_data
+        .split_first()
+        .ok_or(ProgramError::InvalidInstructionData)?;
+
+    match *tag {
+        INITIATE_POOL_TAG => initiate_pool(program_id, accounts),
+        DEPOSIT_TAG => {
+            if rest.len() != 16 {
+                return Err(ProgramError::InvalidInstructionData);
+            }
+            let funded_amount = u64::from_le_bytes(rest[0..8].try_into().unwrap());
+            let offered_amount = u64::from_le_bytes(rest[8..16].try_into().unwrap());
+            deposit(program_id, accounts, funded_amount, offered_amount)
+        }
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+fn verify_key(account_info: &AccountInfo, expected: &Pubkey) -> Result<(), ProgramError> {
+    if account_info.key.ne(expected) {
+        return Err(ProgramError::InvalidSeeds);
+    }
+    Ok(())
+}
+
+fn token_balance(account_info: &AccountInfo) -> Result<u64, ProgramError> {
+    let data = account_info.data.borrow();
+    Ok(StateWithExtensions::<Account>::unpack(&data)?.base.amount)
+}
+
+fn mint_decimals(mint_info: &AccountInfo) -> Result<u8, ProgramError> {
+    let data = mint_info.data.borrow();
+    Ok(StateWithExtensions::<Mint>::unpack(&data)?.base.decimals)
+}
+
+// integer square root by Newton's method, u128::isqrt is too recent for the SBF toolchain
+fn isqrt(value: u128) -> u128 {
+    if value < 2 {
+        return value;
+    }
+    let mut x = value;
+    // ceil(x / 2) is below x for every x >= 2, x / 2 + 1 is not for 2
+    let mut y = x / 2 + x % 2;
+    while y < x {
+        x = y;
+        y = (x + value / x) / 2;
+    }
+    x
+}
+
+// returns the LP tokens minted for a deposit and how much of each side the pool takes for them
+fn deposit_amounts(
+    lp_supply: u64,
+    funded_reserve: u64,
+    offered_reserve: u64,
+    funded_amount: u64,
+    offered_amount: u64,
+) -> Result<(u64, u64, u64), ProgramError> {
+    let lp_supply = lp_supply as u128;
+    let (funded_reserve, offered_reserve) = (funded_reserve as u128, offered_reserve as u128);
+    let (funded_amount, offered_amount) = (funded_amount as u128, offered_amount as u128);
+
+    let (lp_amount, funded_used, offered_used) = if lp_supply == 0 {
+        let lp_amount = isqrt(funded_amount * offered_amount);
+        (lp_amount, funded_amount, offered_amount)
+    } else {
+        if funded_reserve == 0 || offered_reserve == 0 {
+            return Err(ProgramError::InvalidAccountData);
+        }
+
+        // mint for the smaller side and take the other one in proportion,
+        // rounded up so the existing LP tokens are never diluted
+        let lp_amount = (funded_amount * lp_supply / funded_reserve)
+            .min(offered_amount * lp_supply / offered_reserve);
+        (
+            lp_amount,
+            (lp_amount * funded_reserve).div_ceil(lp_supply),
+            (lp_amount * offered_reserve).div_ceil(lp_supply),
+        )
+    };
+
+    if lp_amount == 0 {
+        return Err(ProgramError::InsufficientFunds);
+    }
+
+    let to_u64 = |value: u128| u64::try_from(value).map_err(|_| ProgramError::ArithmeticOverflow);
+    Ok((
+        to_u64(lp_amount)?,
+        to_u64(funded_used)?,
+        to_u64(offered_used)?,
+    ))
+}
+
+fn initiate_pool(program_id: &Pubkey, accounts: &[AccountInfo]) -> Result<(), ProgramError> {
+    let account_info_iter = &mut accounts.iter();
+
+    let payer_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let mint_a_info = next_account_info(account_info_iter)?;
+    let vault_a_info = next_account_info(account_info_iter)?;
+    let token_program_a_info = next_account_info(account_info_iter)?;
+    let mint_b_info = next_account_info(account_info_iter)?;
+    let vault_b_info = next_account_info(account_info_iter)?;
+    let token_program_b_info = next_account_info(account_info_iter)?;
+    let lp_mint_info = next_account_info(account_info_iter)?;
+    let lp_token_program_info = next_account_info(account_info_iter)?;
+    let _ata_program_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    if !payer_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if mint_a_info.key.eq(mint_b_info.key) {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    let (pool, _) = find_pool_address(program_id, mint_a_info.key, mint_b_info.key);
+    verify_key(pool_info, &pool)?;
+
+    let (lp_mint, lp_mint_bump) = find_lp_mint_address(program_id, &pool);
+    verify_key(lp_mint_info, &lp_mint)?;
+
+    if lp_token_program_info.key.ne(&spl_token_2022::id()) {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    if !lp_mint_info.data_is_empty() {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+
+    for (mint_info, vault_info, token_program_info) in [
+        (mint_a_info, vault_a_info, token_program_a_info),
+        (mint_b_info, vault_b_info, token_program_b_info),
+    ] {
+        invoke(
+            &spl_associated_token_account::instruction::create_associated_token_account_idempotent(
+                payer_info.key,
+                pool_info.key,
+                mint_info.key,
+                token_program_info.key,
+            ),
+            &[
+                payer_info.clone(),
+                vault_info.clone(),
+                pool_info.clone(),
+                mint_info.clone(),
+                system_program_info.clone(),
+                token_program_info.clone(),
+            ],
+        )?;
+    }
+
+    invoke_signed(
+        &system_instruction::create_account(
+            payer_info.key,
+            lp_mint_info.key,
+            Rent::get()?.minimum_balance(Mint::LEN),
+            Mint::LEN as u64,
+            lp_token_program_info.key,
+        ),
+        &[payer_info.clone(), lp_mint_info.clone()],
+        &[&[LP_MINT_PDA_SEED, pool_info.key.as_ref(), &[lp_mint_bump]]],
+    )?;
+
+    invoke(
+        &spl_token_2022::instruction::initialize_mint2(
+            lp_token_program_info.key,
+            lp_mint_info.key,
+            pool_info.key,
+            None,
+            LP_MINT_DECIMALS,
+        )?,
+        std::slice::from_ref(lp_mint_info),
+    )?;
+
+    Ok(())
+}
+
+fn deposit(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    funded_amount: u64,
+    offered_amount: u64,
+) -> Result<(), ProgramError> {
+    let account_info_iter = &mut accounts.iter();
+
+    let depositor_info = next_account_info(account_info_iter)?;
+    let depositor_funded_ata_info = next_account_info(account_info_iter)?;
+    let depositor_offered_ata_info = next_account_info(account_info_iter)?;
+    let funded_mint_info = next_account_info(account_info_iter)?;
+    let offered_mint_info = next_account_info(account_info_iter)?;
+    let funded_token_program_info = next_account_info(account_info_iter)?;
+    let offered_token_program_info = next_account_info(account_info_iter)?;
+    let pool_info = next_account_info(account_info_iter)?;
+    let funded_vault_info = next_account_info(account_info_iter)?;
+    let offered_vault_info = next_account_info(account_info_iter)?;
+    let lp_mint_info = next_account_info(account_info_iter)?;
+    let depositor_lp_ata_info = next_account_info(account_info_iter)?;
+    let lp_token_program_info = next_account_info(account_info_iter)?;
+
+    if !depositor_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let (pool, pool_bump) =
+        find_pool_address(program_id, funded_mint_info.key, offered_mint_info.key);
+    verify_key(pool_info, &pool)?;
+
+    let (lp_mint, _) = find_lp_mint_address(program_id, &pool);
+    verify_key(lp_mint_info, &lp_mint)?;
+
+    if lp_token_program_info.key.ne(&spl_token_2022::id()) {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    verify_key(
+        funded_vault_info,
+        &get_associated_token_address_with_program_id(
+            &pool,
+            funded_mint_info.key,
+            funded_token_program_info.key,
+        ),
+    )?;
+    verify_key(
+        offered_vault_info,
+        &get_associated_token_address_with_program_id(
+            &pool,
+            offered_mint_info.key,
+            offered_token_program_info.key,
+        ),
+    )?;
+    verify_key(
+        depositor_lp_ata_info,
+        &get_associated_token_address_with_program_id(
+            depositor_info.key,
+            &lp_mint,
+            lp_token_program_info.key,
+        ),
+    )?;
+
+    let lp_supply = {
+        let data = lp_mint_info.data.borrow();
+        StateWithExtensions::<Mint>::unpack(&data)?.base.supply
+    };
+    let (lp_amount, funded_used, offered_used) = deposit_amounts(
+        lp_supply,
+        token_balance(funded_vault_info)?,
+        token_balance(offered_vault_info)?,
+        funded_amount,
+        offered_amount,
+    )?;
+
+    for (token_program_info, source_info, mint_info, vault_info, amount) in [
+        (
+            funded_token_program_info,
+            depositor_funded_ata_info,
+            funded_mint_info,
+            funded_vault_info,
+            funded_used,
+        ),
+        (
+            offered_token_program_info,
+            depositor_offered_ata_info,
+            offered_mint_info,
+            offered_vault_info,
+            offered_used,
+        ),
+    ] {
+        invoke(
+            &spl_token_2022::instruction::transfer_checked(
+                token_program_info.key,
+                source_info.key,
+                mint_info.key,
+                vault_info.key,
+                depositor_info.key,
+                &[],
+                amount,
+                mint_decimals(mint_info)?,
+            )?,
+            &[
+                source_info.clone(),
+                mint_info.clone(),
+                vault_info.clone(),
+                depositor_info.clone(),
+            ],
+        )?;
+    }
+
+    let (mint_low, mint_high) = sort_mints(funded_mint_info.key, offered_mint_info.key);
+    invoke_signed(
+        &spl_token_2022::instruction::mint_to(
+            lp_token_program_info.key,
+            lp_mint_info.key,
+            depositor_lp_ata_info.key,
+            pool_info.key,
+            &[],
+            lp_amount,
+        )?,
+        &[
+            lp_mint_info.clone(),
+            depositor_lp_ata_info.clone(),
+            pool_info.clone(),
+        ],
+        &[&[
+            POOL_PDA_SEED,
+            mint_low.as_ref(),
+            mint_high.as_ref(),
+            &[pool_bump],
+        ]],
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_isqrt() {
+        for value in [
+            0u128,
+            1,
+            2,
+            3,
+            4,
+            15,
+            16,
+            17,
+            1 << 64,
+            u64::MAX as u128 * u64::MAX as u128,
+        ] {
+            let root = isqrt(value);
+            assert!(root * root <= value);
+            assert!((root + 1)
+                .checked_mul(root + 1)
+                .is_none_or(|square| square > value));
+        }
+    }
+
+    #[test]
+    fn test_first_deposit_mints_the_geometric_mean() {
+        assert_eq!(
+            deposit_amounts(0, 0, 0, 1_000, 2_000),
+            Ok((1_414, 1_000, 2_000))
+        );
+        assert_eq!(deposit_amounts(0, 0, 0, 400, 100), Ok((200, 400, 100)));
+        // the whole deposit is taken, whatever the ratio
+        assert_eq!(
+            deposit_amounts(0, 0, 0, u64::MAX, u64::MAX),
+            Ok((u64::MAX, u64::MAX, u64::MAX))
+        );
+        assert_eq!(
+            deposit_amounts(0, 0, 0, 0, 2_000),
+            Err(ProgramError::InsufficientFunds)
+        );
+    }
+
+    #[test]
+    fn test_later_deposit_keeps_the_reserve_ratio() {
+        // 1_000 / 2_000 reserves backing 1_414 LP tokens
+        let (lp_amount, funded_used, offered_used) =
+            deposit_amounts(1_414, 1_000, 2_000, 500, 5_000).unwrap();
+        assert_eq!(lp_amount, 707);
+        assert_eq!((funded_used, offered_used), (500, 1_000));
+
+        // rounding favours the pool
+        assert_eq!(deposit_amounts(3, 10, 10, 4, 4), Ok((1, 4, 4)));
+        assert_eq!(
+            deposit_amounts(1_414, 0, 2_000, 500, 500),
+            Err(ProgramError::InvalidAccountData)
+        );
+        assert_eq!(
+            deposit_amounts(1_414, 1_000_000, 2_000, 1, 1),
+            Err(ProgramError::InsufficientFunds)
+        );
+    }
+}
@@ -9,6 +9,8 @@ pub const INIT_PUBLISHER_MODE_PAYER_AND_AUTHORITY_ARE_THE_SAME: u8 = 0b00000001;
 pub const UPDATE_PUBLISHER_MODE_PAYER_AND_AUTHORITY_ARE_THE_SAME: u8 = 0b00000001;
 pub const UPDATE_PUBLISHER_MODE_AUTHORITY_AND_TRANSFER_ARE_THE_SAME: u8 = 0b00000010;
 pub const UPDATE_PUBLISHER_MODE_TRANSFER_AND_APPROVAL_ARE_THE_SAME: u8 = 0b00000100;
+pub const UPDATE_PUBLISHER_MODE_SET_TRANSFER_AUTHORITY: u8 = 0b00001000;
+pub const UPDATE_PUBLISHER_MODE_SET_APPROVAL_AUTHORITY: u8 = 0b00010000;
 
 pub const INIT_PUBLISHER_TOKEN_MODE_PAYER_AND_AUTHORITY_ARE_THE_SAME: u8 = 0b00000001;
 pub const INIT_PUBLISHER_TOKEN_MODE_TOKEN_PROGRAM_AND_SYSTEM_ARE_THE_SAME: u8 = 0b00000010;
@@ -16,6 +18,7 @@ pub const INIT_PUBLISHER_TOKEN_MODE_TOKEN_PROGRAM_AND_SYSTEM_ARE_THE_SAME: u8 =
 pub const INIT_OFFERING_MODE_PAYER_AND_PROMOTER_ARE_THE_SAME: u8 = 0b00000001;
 pub const INIT_OFFERING_MODE_FUNDED_AND_SYSTEM_ARE_THE_SAME: u8 = 0b00000010;
 pub const INIT_OFFERING_MODE_OFFERED_AND_SYSTEM_ARE_THE_SAME: u8 = 0b00000100;
+pub const INIT_OFFERING_MODE_PAYMENT_AND_SYSTEM_ARE_THE_SAME: u8 = 0b00001000;
 
 pub const FINALIZE_OFFERING_MODE_PAYER_AND_PROMOTER_ARE_THE_SAME: u8 = 0b00000001;
 pub const FINALIZE_OFFERING_MODE_PAYMENT_AND_SYSTEM_ARE_THE_SAME: u8 = 0b00000010;
@@ -34,6 +37,7 @@ pub const PUBLISHER_TOKEN_PDA_SEED: &[u8] = &[2];
 pub const OFFERING_PDA_SEED: &[u8] = &[3];
 pub const PURCHASE_PDA_SEED: &[u8] = &[5];
 pub const CUSTODIAL_PDA_SEED: &[u8] = &[6];
+pub const MARKET_POOL_PDA_SEED: &[u8] = &[7];
 
 pub const UNINITIALIZED_PDA_TYPE: u8 = 0;
 pub const CLOSED_PDA_TYPE: u8 = 1;
@@ -42,6 +46,10 @@ pub const PUBLISHER_TOKEN_PDA_TYPE: u8 = 3;
 pub const OFFERING_PDA_TYPE: u8 = 4;
 pub const PURCHASE_PDA_TYPE: u8 = 5;
 
+// layout version written right after the discriminator of every account
+pub const LEGACY_ACCOUNT_VERSION: u8 = 0;
+pub const ACCOUNT_VERSION: u8 = 1;
+
 pub const INITIATE_PUBLISHER_TAG: u8 = 0;
 pub const UPDATE_PUBLISHER_TAG: u8 = 1;
 pub const CLAIM_PUBLISHER_AUTHORITY_TAG: u8 = 2;
@@ -57,7 +65,14 @@ pub const INITITATE_PUBLISHER_TOKEN_TAG: u8 = 11;
 pub const UPDATE_PUBLISHER_TOKEN_TAG: u8 = 12;
 pub const FINALIZE_OFFERING_CREATION_TAG: u8 = 13;
 pub const UPDATE_OFFERING_TARGET_TAG: u8 = 14;
+pub const DEPOSIT_MARKET_POOL_TAG: u8 = 15;
+pub const MIGRATE_ACCOUNT_TAG: u8 = 16;
+pub const CLOSE_PUBLISHER_ACCOUNT_TAG: u8 = 17;
+pub const CLOSE_PUBLISHER_TOKEN_ACCOUNT_TAG: u8 = 18;
+pub const CLOSE_OFFERING_ACCOUNT_TAG: u8 = 19;
+pub const CLOSE_PURCHASE_ACCOUNT_TAG: u8 = 20;
 
+// the blobs logged after each event ID are described by its struct in crate::events
 pub const PUBLISHER_INITIATED_EVENT: &[u8] = &[0];
 pub const PUBLISHER_UPDATED_EVENT: &[u8] = &[1];
 pub const PUBLISHER_AUTHORITY_CLAIMED_EVENT: &[u8] = &[2];
@@ -72,4 +87,11 @@ pub const PURCHASE_REFUNDED_EVENT: &[u8] = &[10];
 pub const PURCHASE_DISTRIBUTION_DISTRIBUTED_EVENT: &[u8] = &[11];
 pub const PUBLISHER_TOKEN_INITIATED_EVENT: &[u8] = &[12];
 pub const PUBLISHER_TOKEN_UPDATED_EVENT: &[u8] = &[13];
-pub const OFFERING_CREATION_FINALIZED_EVENT: &[u8] = &[14];
\ No newline at end of file
+pub const OFFERING_CREATION_FINALIZED_EVENT: &[u8] = &[14];
+pub const OFFERING_TARGET_UPDATED_EVENT: &[u8] = &[15];
+pub const OFFERING_MARKET_POOL_DEPOSITED_EVENT: &[u8] = &[16];
+pub const ACCOUNT_MIGRATED_EVENT: &[u8] = &[17];
+pub const PUBLISHER_ACCOUNT_CLOSED_EVENT: &[u8] = &[18];
+pub const PUBLISHER_TOKEN_ACCOUNT_CLOSED_EVENT: &[u8] = &[19];
+pub const OFFERING_ACCOUNT_CLOSED_EVENT: &[u8] = &[20];
+pub const PURCHASE_ACCOUNT_CLOSED_EVENT: &[u8] = &[21];
@@ -64,14 +64,42 @@ pub const OFFERING_IS_STILL_PURCHASEABLE_ERROR: ProgramError = ProgramError::Cus
 pub const OFFERING_UNHANDLED_STATE_ERROR: ProgramError = ProgramError::Custom(328);
 pub const OFFERING_MAX_FUNDING_EXCEEDED_ERROR: ProgramError = ProgramError::Custom(329);
 pub const OFFERING_MIN_FUNDING_NOT_MET_ERROR: ProgramError = ProgramError::Custom(330);
-pub const PUBLISHER_TOKEN_IS_NOT_ALLOWED_AS_OFFER_CREATION_PAYMENT_ERROR: ProgramError = ProgramError::Custom(331);
+pub const PUBLISHER_TOKEN_IS_NOT_ALLOWED_AS_OFFER_CREATION_PAYMENT_ERROR: ProgramError =
+    ProgramError::Custom(331);
 pub const PUBLISHER_TOKEN_IS_INVALID: ProgramError = ProgramError::Custom(332);
 pub const PUBLISHER_TOKEN_IS_DISABLED_ERROR: ProgramError = ProgramError::Custom(333);
 pub const OFFERING_IS_NOT_APPROVED_BY_PUBLISHER_ERROR: ProgramError = ProgramError::Custom(334);
 pub const OFFERING_OFFERED_MINT_IS_INVALID_ERROR: ProgramError = ProgramError::Custom(335);
 pub const OFFERING_OFFER_IS_NOT_PROVIDED_ERROR: ProgramError = ProgramError::Custom(336);
 pub const OFFERING_FUNDS_IS_ALREADY_WITHDRAWN_ERROR: ProgramError = ProgramError::Custom(337);
-pub const OFFERING_MARKET_POOL_PERCENTAGE_IS_INVALID_ERROR: ProgramError = ProgramError::Custom(338);
+pub const OFFERING_MARKET_POOL_PERCENTAGE_IS_INVALID_ERROR: ProgramError =
+    ProgramError::Custom(338);
 pub const OFFERING_INSUFFICIENT_FUNDS_ERROR: ProgramError = ProgramError::Custom(339);
 pub const TRANSFER_INSUFFICIENT_FUNDS_ERROR: ProgramError = ProgramError::Custom(340);
-pub const OFFERING_RELEASE_TIME_OVERFLOW_ERROR: ProgramError = ProgramError::Custom(341);
\ No newline at end of file
+pub const OFFERING_RELEASE_TIME_OVERFLOW_ERROR: ProgramError = ProgramError::Custom(341);
+pub const OFFERING_TARGET_IS_INVALID_ERROR: ProgramError = ProgramError::Custom(342);
+pub const OFFERING_OFFER_IS_ALREADY_PROVIDED_ERROR: ProgramError = ProgramError::Custom(343);
+pub const OFFERING_IS_REJECTED_BY_PUBLISHER_ERROR: ProgramError = ProgramError::Custom(344);
+pub const OFFERING_FUNDS_IS_ALREADY_DEPOSITED_TO_MARKET_POOL_ERROR: ProgramError =
+    ProgramError::Custom(345);
+pub const ACCOUNT_IS_OUTDATED_ERROR: ProgramError = ProgramError::Custom(346);
+pub const ACCOUNT_IS_ALREADY_MIGRATED_ERROR: ProgramError = ProgramError::Custom(347);
+pub const PUBLISHER_IS_OPEN_FOR_OFFERING_CREATION_ERROR: ProgramError = ProgramError::Custom(348);
+pub const PUBLISHER_TOKEN_IS_NOT_DISABLED_ERROR: ProgramError = ProgramError::Custom(349);
+pub const OFFERING_FUNDS_IS_NOT_WITHDRAWN_ERROR: ProgramError = ProgramError::Custom(350);
+pub const OFFERING_FUNDS_IS_NOT_DEPOSITED_TO_MARKET_POOL_ERROR: ProgramError =
+    ProgramError::Custom(351);
+pub const OFFERING_HAS_PURCHASES_ERROR: ProgramError = ProgramError::Custom(352);
+pub const PURCHASE_IS_NOT_DISTRIBUTED_ERROR: ProgramError = ProgramError::Custom(353);
+pub const INVALID_EVENT_ERROR: ProgramError = ProgramError::Custom(354);
+pub const INVALID_OFFERING_PAYMENT_MINT_ERROR: ProgramError = ProgramError::Custom(355);
+pub const INVALID_OFFER_CREATION_PRICE_ERROR: ProgramError = ProgramError::Custom(356);
+pub const INVALID_MARKET_POOL_PROGRAM_ERROR: ProgramError = ProgramError::Custom(357);
+pub const MARKET_POOL_LP_IS_NOT_RECEIVED_ERROR: ProgramError = ProgramError::Custom(358);
+pub const PUBLISHER_HAS_PUBLISHER_TOKENS_ERROR: ProgramError = ProgramError::Custom(359);
+pub const PUBLISHER_TOKEN_CUSTODIAL_BALANCE_IS_NOT_EMPTY_ERROR: ProgramError =
+    ProgramError::Custom(360);
+pub const OFFERING_IS_NOT_DISTRIBUTED_ERROR: ProgramError = ProgramError::Custom(361);
+pub const PUBLISHER_HAS_OFFERINGS_ERROR: ProgramError = ProgramError::Custom(362);
+pub const PUBLISHER_HAS_UNCOUNTED_ACCOUNTS_ERROR: ProgramError = ProgramError::Custom(363);
+pub const INVALID_MARKET_POOL_LP_MINT_ERROR: ProgramError = ProgramError::Custom(364);
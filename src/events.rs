@@ -0,0 +1,587 @@
+use solana_program::{
+    clock::UnixTimestamp, log, program_error::ProgramError, program_pack::Pack, pubkey::Pubkey,
+};
+
+use crate::{
+    constant::{
+        ACCOUNT_MIGRATED_EVENT, OFFERING_ACCOUNT_CLOSED_EVENT, OFFERING_APPROVAL_EVENT,
+        OFFERING_CLOSED_EVENT, OFFERING_CREATION_FINALIZED_EVENT, OFFERING_INITIATED_EVENT,
+        OFFERING_MARKET_POOL_DEPOSITED_EVENT, OFFERING_TARGET_UPDATED_EVENT,
+        PUBLISHER_ACCOUNT_CLOSED_EVENT, PUBLISHER_AUTHORITY_CLAIMED_EVENT,
+        PUBLISHER_INITIATED_EVENT, PUBLISHER_TOKEN_ACCOUNT_CLOSED_EVENT,
+        PUBLISHER_TOKEN_INITIATED_EVENT, PUBLISHER_TOKEN_UPDATED_EVENT,
+        PUBLISHER_TOKEN_WITHDRAWAL_EVENT, PUBLISHER_UPDATED_EVENT, PUBLISHER_WITHDRAWAL_EVENT,
+        PURCHASE_ACCOUNT_CLOSED_EVENT, PURCHASE_ADDED_EVENT,
+        PURCHASE_DISTRIBUTION_DISTRIBUTED_EVENT, PURCHASE_INITIATED_EVENT, PURCHASE_REFUNDED_EVENT,
+    },
+    error::INVALID_EVENT_ERROR,
+    state::{
+        offering::OfferingAccount, publisher::PublisherAccount,
+        publisher_token::PublisherTokenAccount, purchase::PurchaseAccount,
+        variable_len_pack::VariableLenPack,
+    },
+};
+
+/// An event logged with `sol_log_data`. The first blob of the log is the event
+/// ID, followed by one blob per field in the order they are declared. Integers
+/// are little endian, and accounts are logged in their packed layout.
+pub trait Event: Sized {
+    const ID: &'static [u8];
+
+    /// Serializes the fields into the blobs following the event ID.
+    fn pack_fields(&self, fields: &mut EventWriter) -> Result<(), ProgramError>;
+
+    /// Deserializes the fields from the blobs following the event ID.
+    fn unpack_fields(fields: &[Vec<u8>]) -> Result<Self, ProgramError>;
+}
+
+/// Logs the event, this is how every processor emits its events.
+pub fn emit<E: Event>(event: &E) -> Result<(), ProgramError> {
+    let mut fields = EventWriter::default();
+    event.pack_fields(&mut fields)?;
+
+    let mut data: [&[u8]; MAX_NUM_OF_EVENT_FIELDS + 1] = [&[]; MAX_NUM_OF_EVENT_FIELDS + 1];
+    data[0] = E::ID;
+    for (blob, field) in data[1..].iter_mut().zip(fields.iter()) {
+        *blob = field;
+    }
+
+    log::sol_log_data(&data[..fields.num_of_fields + 1]);
+
+    Ok(())
+}
+
+// declares the events decoded by `decode_event`, each one a `ContractEvent`
+// variant wrapping the event struct of the same name
+macro_rules! contract_events {
+    ($($variant:ident($event:ident),)*) => {
+        /// Decodes the blobs of a `Program data:` log line, i.e. its base64 encoded
+        /// words after they are decoded, back into the event that was emitted.
+        pub fn decode_event(data: &[Vec<u8>]) -> Result<ContractEvent, ProgramError> {
+            let (id, fields) = data.split_first().ok_or(INVALID_EVENT_ERROR)?;
+
+            let event = match id.as_slice() {
+                $(<$event as Event>::ID => ContractEvent::$variant($event::unpack_fields(fields)?),)*
+                _ => return Err(INVALID_EVENT_ERROR),
+            };
+
+            Ok(event)
+        }
+
+        #[derive(Clone, Debug, PartialEq)]
+        pub enum ContractEvent {
+            $($variant($event),)*
+        }
+    };
+}
+
+contract_events! {
+    PublisherInitiated(PublisherInitiatedEvent),
+    PublisherUpdated(PublisherUpdatedEvent),
+    PublisherAuthorityClaimed(PublisherAuthorityClaimedEvent),
+    PublisherWithdrawal(PublisherWithdrawalEvent),
+    PublisherTokenWithdrawal(PublisherTokenWithdrawalEvent),
+    OfferingInitiated(OfferingInitiatedEvent),
+    OfferingApproval(OfferingApprovalEvent),
+    OfferingClosed(OfferingClosedEvent),
+    PurchaseInitiated(PurchaseInitiatedEvent),
+    PurchaseAdded(PurchaseAddedEvent),
+    PurchaseRefunded(PurchaseRefundedEvent),
+    PurchaseDistributionDistributed(PurchaseDistributionDistributedEvent),
+    PublisherTokenInitiated(PublisherTokenInitiatedEvent),
+    PublisherTokenUpdated(PublisherTokenUpdatedEvent),
+    OfferingCreationFinalized(OfferingCreationFinalizedEvent),
+    OfferingTargetUpdated(OfferingTargetUpdatedEvent),
+    OfferingMarketPoolDeposited(OfferingMarketPoolDepositedEvent),
+    AccountMigrated(AccountMigratedEvent),
+    PublisherAccountClosed(PublisherAccountClosedEvent),
+    PublisherTokenAccountClosed(PublisherTokenAccountClosedEvent),
+    OfferingAccountClosed(OfferingAccountClosedEvent),
+    PurchaseAccountClosed(PurchaseAccountClosedEvent),
+}
+
+// declares an event struct whose fields are logged one blob each, in the order
+// they are declared, as their `EventField` layout
+macro_rules! event {
+    (
+        $(#[$attr:meta])*
+        pub struct $event:ident = $id:ident {
+            $($(#[$field_attr:meta])* pub $field:ident: $ty:ty,)*
+        }
+    ) => {
+        $(#[$attr])*
+        #[derive(Clone, Debug, PartialEq)]
+        pub struct $event {
+            $($(#[$field_attr])* pub $field: $ty,)*
+        }
+
+        impl Event for $event {
+            const ID: &'static [u8] = $id;
+
+            fn pack_fields(&self, fields: &mut EventWriter) -> Result<(), ProgramError> {
+                $(self.$field.write(fields)?;)*
+
+                Ok(())
+            }
+
+            fn unpack_fields(fields: &[Vec<u8>]) -> Result<Self, ProgramError> {
+                let mut fields = EventFields::new(fields);
+                let event = Self {
+                    $($field: EventField::read(&mut fields)?,)*
+                };
+                fields.finish()?;
+
+                Ok(event)
+            }
+        }
+    };
+}
+
+event! {
+    pub struct PublisherInitiatedEvent = PUBLISHER_INITIATED_EVENT {
+        pub timestamp: UnixTimestamp,
+        pub publisher_pda: Pubkey,
+        pub publisher: PublisherAccount,
+        pub payer: Pubkey,
+        pub authority: Pubkey,
+    }
+}
+
+event! {
+    pub struct PublisherUpdatedEvent = PUBLISHER_UPDATED_EVENT {
+        pub timestamp: UnixTimestamp,
+        pub publisher_pda: Pubkey,
+        pub publisher: PublisherAccount,
+        pub authority: Pubkey,
+    }
+}
+
+event! {
+    pub struct PublisherAuthorityClaimedEvent = PUBLISHER_AUTHORITY_CLAIMED_EVENT {
+        pub timestamp: UnixTimestamp,
+        pub publisher_pda: Pubkey,
+        pub publisher: PublisherAccount,
+        pub previous_authority: Pubkey,
+        pub claimer: Pubkey,
+    }
+}
+
+event! {
+    pub struct PublisherWithdrawalEvent = PUBLISHER_WITHDRAWAL_EVENT {
+        pub timestamp: UnixTimestamp,
+        pub publisher_pda: Pubkey,
+        pub custodial_pda: Pubkey,
+        pub authority: Pubkey,
+        pub amount: u64,
+    }
+}
+
+event! {
+    pub struct PublisherTokenWithdrawalEvent = PUBLISHER_TOKEN_WITHDRAWAL_EVENT {
+        pub timestamp: UnixTimestamp,
+        pub publisher_pda: Pubkey,
+        pub custodial_pda: Pubkey,
+        pub authority: Pubkey,
+        pub mint: Pubkey,
+        pub amount: u64,
+    }
+}
+
+event! {
+    pub struct OfferingInitiatedEvent = OFFERING_INITIATED_EVENT {
+        pub timestamp: UnixTimestamp,
+        pub offering_pda: Pubkey,
+        pub offering: OfferingAccount,
+        pub payer: Pubkey,
+        pub promoter: Pubkey,
+        pub payment_mint: Pubkey,
+        pub offer_creation_price: u64,
+    }
+}
+
+event! {
+    pub struct OfferingApprovalEvent = OFFERING_APPROVAL_EVENT {
+        pub timestamp: UnixTimestamp,
+        pub offering_pda: Pubkey,
+        pub offering: OfferingAccount,
+        pub approval_authority: Pubkey,
+        pub is_approved: bool,
+        pub reason_hash: Option<[u8; 32]>,
+    }
+}
+
+event! {
+    pub struct OfferingClosedEvent = OFFERING_CLOSED_EVENT {
+        pub timestamp: UnixTimestamp,
+        pub offering_pda: Pubkey,
+        pub offering: OfferingAccount,
+        pub promoter: Pubkey,
+        pub withdrawn_amount: u64,
+        pub returned_quantity: u64,
+    }
+}
+
+event! {
+    pub struct PurchaseInitiatedEvent = PURCHASE_INITIATED_EVENT {
+        pub timestamp: UnixTimestamp,
+        pub purchase_pda: Pubkey,
+        pub purchase: PurchaseAccount,
+        pub offering_pda: Pubkey,
+        pub purchaser: Pubkey,
+        // the amount credited to the purchase, net of any Token-2022 transfer fee
+        pub amount: u64,
+    }
+}
+
+// same layout as PurchaseInitiatedEvent, emitted when the purchaser adds to an existing purchase
+event! {
+    pub struct PurchaseAddedEvent = PURCHASE_ADDED_EVENT {
+        pub timestamp: UnixTimestamp,
+        pub purchase_pda: Pubkey,
+        pub purchase: PurchaseAccount,
+        pub offering_pda: Pubkey,
+        pub purchaser: Pubkey,
+        pub amount: u64,
+    }
+}
+
+event! {
+    pub struct PurchaseRefundedEvent = PURCHASE_REFUNDED_EVENT {
+        pub timestamp: UnixTimestamp,
+        pub purchase_pda: Pubkey,
+        pub offering_pda: Pubkey,
+        pub purchaser: Pubkey,
+        pub funded_amount: u64,
+    }
+}
+
+event! {
+    pub struct PurchaseDistributionDistributedEvent = PURCHASE_DISTRIBUTION_DISTRIBUTED_EVENT {
+        pub timestamp: UnixTimestamp,
+        pub purchase_pda: Pubkey,
+        pub offering_pda: Pubkey,
+        pub purchaser: Pubkey,
+        pub vesting_index: u8,
+        pub quantity: u64,
+    }
+}
+
+event! {
+    pub struct PublisherTokenInitiatedEvent = PUBLISHER_TOKEN_INITIATED_EVENT {
+        pub timestamp: UnixTimestamp,
+        pub publisher_token_pda: Pubkey,
+        pub publisher_token: PublisherTokenAccount,
+        pub publisher_pda: Pubkey,
+        pub authority: Pubkey,
+    }
+}
+
+event! {
+    pub struct PublisherTokenUpdatedEvent = PUBLISHER_TOKEN_UPDATED_EVENT {
+        pub timestamp: UnixTimestamp,
+        pub publisher_token_pda: Pubkey,
+        pub publisher_token: PublisherTokenAccount,
+        pub publisher_pda: Pubkey,
+        pub authority: Pubkey,
+    }
+}
+
+event! {
+    pub struct OfferingCreationFinalizedEvent = OFFERING_CREATION_FINALIZED_EVENT {
+        pub timestamp: UnixTimestamp,
+        pub offering_pda: Pubkey,
+        pub offering: OfferingAccount,
+        pub promoter: Pubkey,
+        pub escrow_quantity: u64,
+    }
+}
+
+event! {
+    pub struct OfferingTargetUpdatedEvent = OFFERING_TARGET_UPDATED_EVENT {
+        pub timestamp: UnixTimestamp,
+        pub offering_pda: Pubkey,
+        pub offering: OfferingAccount,
+        pub promoter: Pubkey,
+    }
+}
+
+event! {
+    pub struct OfferingMarketPoolDepositedEvent = OFFERING_MARKET_POOL_DEPOSITED_EVENT {
+        pub timestamp: UnixTimestamp,
+        pub offering_pda: Pubkey,
+        pub offering: OfferingAccount,
+        pub pool_program: Pubkey,
+        pub funded_amount: u64,
+        pub offered_amount: u64,
+        // LP tokens the market pool PDA received for the deposit
+        pub lp_mint: Pubkey,
+        pub lp_amount: u64,
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AccountMigratedEvent {
+    pub timestamp: UnixTimestamp,
+    pub pda: Pubkey,
+    pub previous_version: u8,
+    pub version: u8,
+    pub publisher: PublisherAccount,
+}
+
+// written by hand since both versions share a single blob
+impl Event for AccountMigratedEvent {
+    const ID: &'static [u8] = ACCOUNT_MIGRATED_EVENT;
+
+    fn pack_fields(&self, fields: &mut EventWriter) -> Result<(), ProgramError> {
+        self.timestamp.write(fields)?;
+        self.pda.write(fields)?;
+        fields.bytes(&[self.previous_version, self.version]);
+        self.publisher.write(fields)?;
+
+        Ok(())
+    }
+
+    fn unpack_fields(fields: &[Vec<u8>]) -> Result<Self, ProgramError> {
+        let mut fields = EventFields::new(fields);
+        let timestamp = EventField::read(&mut fields)?;
+        let pda = EventField::read(&mut fields)?;
+        let [previous_version, version] = fields.array()?;
+        let publisher = EventField::read(&mut fields)?;
+        fields.finish()?;
+
+        Ok(Self {
+            timestamp,
+            pda,
+            previous_version,
+            version,
+            publisher,
+        })
+    }
+}
+
+event! {
+    pub struct PublisherAccountClosedEvent = PUBLISHER_ACCOUNT_CLOSED_EVENT {
+        pub timestamp: UnixTimestamp,
+        pub publisher_pda: Pubkey,
+        pub authority: Pubkey,
+        pub receiver: Pubkey,
+        pub custodial_balance: u64,
+    }
+}
+
+event! {
+    pub struct PublisherTokenAccountClosedEvent = PUBLISHER_TOKEN_ACCOUNT_CLOSED_EVENT {
+        pub timestamp: UnixTimestamp,
+        pub publisher_token_pda: Pubkey,
+        pub publisher_pda: Pubkey,
+        pub mint: Pubkey,
+        pub receiver: Pubkey,
+    }
+}
+
+event! {
+    pub struct OfferingAccountClosedEvent = OFFERING_ACCOUNT_CLOSED_EVENT {
+        pub timestamp: UnixTimestamp,
+        pub offering_pda: Pubkey,
+        pub promoter: Pubkey,
+        pub receiver: Pubkey,
+    }
+}
+
+event! {
+    pub struct PurchaseAccountClosedEvent = PURCHASE_ACCOUNT_CLOSED_EVENT {
+        pub timestamp: UnixTimestamp,
+        pub purchase_pda: Pubkey,
+        pub offering_pda: Pubkey,
+        pub purchaser: Pubkey,
+        pub receiver: Pubkey,
+    }
+}
+
+// the most blobs an event logs after its ID
+const MAX_NUM_OF_EVENT_FIELDS: usize = 8;
+
+/// Writes the blobs of an event back to back into a single buffer, the
+/// counterpart of `EventFields`.
+#[derive(Default)]
+pub struct EventWriter {
+    data: Vec<u8>,
+    ends: [usize; MAX_NUM_OF_EVENT_FIELDS],
+    num_of_fields: usize,
+}
+
+impl EventWriter {
+    fn iter(&self) -> impl Iterator<Item = &[u8]> {
+        let ends = &self.ends[..self.num_of_fields];
+        ends.iter().scan(0, |start, &end| {
+            let field = &self.data[*start..end];
+            *start = end;
+            Some(field)
+        })
+    }
+
+    // extends the buffer by a blob of len bytes and returns it to be filled
+    fn next(&mut self, len: usize) -> &mut [u8] {
+        assert!(
+            self.num_of_fields < MAX_NUM_OF_EVENT_FIELDS,
+            "too many event fields"
+        );
+        let start = self.data.len();
+        self.data.resize(start + len, 0);
+        self.ends[self.num_of_fields] = self.data.len();
+        self.num_of_fields += 1;
+        &mut self.data[start..]
+    }
+
+    fn bytes(&mut self, bytes: &[u8]) {
+        self.next(bytes.len()).copy_from_slice(bytes);
+    }
+}
+
+// reads the blobs of an event in the order they were logged
+struct EventFields<'a> {
+    fields: std::slice::Iter<'a, Vec<u8>>,
+}
+
+impl<'a> EventFields<'a> {
+    fn new(fields: &'a [Vec<u8>]) -> Self {
+        Self {
+            fields: fields.iter(),
+        }
+    }
+
+    fn next(&mut self) -> Result<&'a [u8], ProgramError> {
+        self.fields
+            .next()
+            .map(Vec::as_slice)
+            .ok_or(INVALID_EVENT_ERROR)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], ProgramError> {
+        self.next()?.try_into().map_err(|_| INVALID_EVENT_ERROR)
+    }
+
+    fn finish(mut self) -> Result<(), ProgramError> {
+        if self.fields.next().is_some() {
+            return Err(INVALID_EVENT_ERROR);
+        }
+
+        Ok(())
+    }
+}
+
+// a value logged as a single blob of an event
+trait EventField: Sized {
+    fn write(&self, fields: &mut EventWriter) -> Result<(), ProgramError>;
+
+    fn read(fields: &mut EventFields) -> Result<Self, ProgramError>;
+}
+
+// covers UnixTimestamp as well
+impl EventField for i64 {
+    fn write(&self, fields: &mut EventWriter) -> Result<(), ProgramError> {
+        fields.bytes(&self.to_le_bytes());
+        Ok(())
+    }
+
+    fn read(fields: &mut EventFields) -> Result<Self, ProgramError> {
+        Ok(Self::from_le_bytes(fields.array()?))
+    }
+}
+
+impl EventField for u64 {
+    fn write(&self, fields: &mut EventWriter) -> Result<(), ProgramError> {
+        fields.bytes(&self.to_le_bytes());
+        Ok(())
+    }
+
+    fn read(fields: &mut EventFields) -> Result<Self, ProgramError> {
+        Ok(Self::from_le_bytes(fields.array()?))
+    }
+}
+
+impl EventField for u8 {
+    fn write(&self, fields: &mut EventWriter) -> Result<(), ProgramError> {
+        fields.bytes(&[*self]);
+        Ok(())
+    }
+
+    fn read(fields: &mut EventFields) -> Result<Self, ProgramError> {
+        let [value] = fields.array()?;
+        Ok(value)
+    }
+}
+
+impl EventField for bool {
+    fn write(&self, fields: &mut EventWriter) -> Result<(), ProgramError> {
+        (*self as u8).write(fields)
+    }
+
+    fn read(fields: &mut EventFields) -> Result<Self, ProgramError> {
+        match u8::read(fields)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(INVALID_EVENT_ERROR),
+        }
+    }
+}
+
+impl EventField for Pubkey {
+    fn write(&self, fields: &mut EventWriter) -> Result<(), ProgramError> {
+        fields.bytes(self.as_ref());
+        Ok(())
+    }
+
+    fn read(fields: &mut EventFields) -> Result<Self, ProgramError> {
+        Ok(Pubkey::new_from_array(fields.array()?))
+    }
+}
+
+// logged as an empty blob when there is no hash
+impl EventField for Option<[u8; 32]> {
+    fn write(&self, fields: &mut EventWriter) -> Result<(), ProgramError> {
+        fields.bytes(self.as_ref().map_or(&[], |hash| &hash[..]));
+        Ok(())
+    }
+
+    // an empty blob does not survive every log parser, so a missing one is read as None as well
+    fn read(fields: &mut EventFields) -> Result<Self, ProgramError> {
+        match fields.fields.next() {
+            None => Ok(None),
+            Some(hash) if hash.is_empty() => Ok(None),
+            Some(hash) => Ok(Some(
+                hash.as_slice()
+                    .try_into()
+                    .map_err(|_| INVALID_EVENT_ERROR)?,
+            )),
+        }
+    }
+}
+
+impl EventField for OfferingAccount {
+    fn write(&self, fields: &mut EventWriter) -> Result<(), ProgramError> {
+        self.pack(fields.next(self.get_packed_len()))
+    }
+
+    fn read(fields: &mut EventFields) -> Result<Self, ProgramError> {
+        Self::unpack_from_slice(fields.next()?)
+    }
+}
+
+// accounts with a fixed layout are logged as packed
+macro_rules! packed_event_field {
+    ($($account:ty),*) => {
+        $(impl EventField for $account {
+            fn write(&self, fields: &mut EventWriter) -> Result<(), ProgramError> {
+                self.pack_into_slice(fields.next(Self::LEN));
+                Ok(())
+            }
+
+            fn read(fields: &mut EventFields) -> Result<Self, ProgramError> {
+                Self::unpack_from_slice(fields.next()?)
+            }
+        })*
+    };
+}
+
+packed_event_field!(PublisherAccount, PublisherTokenAccount, PurchaseAccount);
@@ -0,0 +1,1732 @@
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program,
+};
+use spl_associated_token_account_client::address::get_associated_token_address_with_program_id;
+
+use crate::{
+    constant::{
+        APPROVAL_OFFERING_TAG, CLAIM_PUBLISHER_AUTHORITY_TAG, CLOSE_OFFERING_ACCOUNT_TAG,
+        CLOSE_OFFERING_TAG, CLOSE_PUBLISHER_ACCOUNT_TAG, CLOSE_PUBLISHER_TOKEN_ACCOUNT_TAG,
+        CLOSE_PURCHASE_ACCOUNT_TAG, DEPOSIT_MARKET_POOL_TAG, DISTRIBUTE_TAG,
+        FINALIZE_OFFERING_CREATION_TAG, FINALIZE_OFFERING_MODE_PAYER_AND_PROMOTER_ARE_THE_SAME,
+        INITIATE_OFFERING_TAG, INITIATE_PUBLISHER_TAG, INITIATE_PURCHASE_TAG,
+        INITITATE_PUBLISHER_TOKEN_TAG, INIT_OFFERING_MODE_FUNDED_AND_SYSTEM_ARE_THE_SAME,
+        INIT_OFFERING_MODE_OFFERED_AND_SYSTEM_ARE_THE_SAME,
+        INIT_OFFERING_MODE_PAYER_AND_PROMOTER_ARE_THE_SAME,
+        INIT_OFFERING_MODE_PAYMENT_AND_SYSTEM_ARE_THE_SAME,
+        INIT_PUBLISHER_MODE_PAYER_AND_AUTHORITY_ARE_THE_SAME,
+        INIT_PUBLISHER_TOKEN_MODE_PAYER_AND_AUTHORITY_ARE_THE_SAME,
+        INIT_PUBLISHER_TOKEN_MODE_TOKEN_PROGRAM_AND_SYSTEM_ARE_THE_SAME,
+        INIT_PURCHASE_MODE_FUNDED_AND_SYSTEM_ARE_THE_SAME,
+        INIT_PURCHASE_MODE_PAYER_AND_SUBJECT_ARE_THE_SAME, MIGRATE_ACCOUNT_TAG,
+        OFFERING_MODE_FUNDED_AND_SYSTEM_ARE_THE_SAME,
+        OFFERING_MODE_PAYER_AND_PROMOTER_ARE_THE_SAME,
+        PURCHASE_MODE_FUNDED_AND_SYSTEM_ARE_THE_SAME, PURCHASE_MODE_PAYER_AND_SUBJECT_ARE_THE_SAME,
+        REFUND_PURCHASE_TAG, UPDATE_OFFERING_TARGET_TAG,
+        UPDATE_PUBLISHER_MODE_AUTHORITY_AND_TRANSFER_ARE_THE_SAME,
+        UPDATE_PUBLISHER_MODE_PAYER_AND_AUTHORITY_ARE_THE_SAME,
+        UPDATE_PUBLISHER_MODE_SET_APPROVAL_AUTHORITY, UPDATE_PUBLISHER_MODE_SET_TRANSFER_AUTHORITY,
+        UPDATE_PUBLISHER_MODE_TRANSFER_AND_APPROVAL_ARE_THE_SAME, UPDATE_PUBLISHER_TAG,
+        UPDATE_PUBLISHER_TOKEN_TAG, WITHDRAW_PUBLISHER_TAG, WITHDRAW_PUBLISHER_TOKEN_TAG,
+    },
+    error::{
+        INVALID_INSTRUCTION_ERROR, INVALID_NUM_OF_VESTINGS_ERROR, INVALID_TAG_ERROR,
+        OFFERING_OFFERED_MINT_IS_INVALID_ERROR,
+    },
+    pda::{
+        find_custodial_address, find_market_pool_address, find_offering_address,
+        find_publisher_address, find_publisher_token_address, find_purchase_address,
+    },
+    state::offering::{OfferingVesting, MAX_NUM_OF_VESTINGS},
+};
+
+/// Instructions understood by the program. Every payload starts with its tag
+/// byte, followed by the mode bits (when the instruction has any) and the
+/// little-endian encoded arguments in declaration order.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ContractInstruction {
+    InitiatePublisher {
+        mode: u8,
+        publisher_seed: Pubkey,
+    },
+    UpdatePublisher {
+        mode: u8,
+        is_open_for_offering_creation: bool,
+    },
+    ClaimPublisherAuthority,
+    WithdrawPublisher {
+        amount: u64,
+    },
+    WithdrawPublisherToken {
+        amount: u64,
+    },
+    InitiateOffering {
+        mode: u8,
+        offering_seed: Pubkey,
+        target: OfferingTarget,
+        offer_quantity: u64,
+        market_pool_percentage: u8,
+        metadata_cid: Vec<u8>,
+        vestings: Vec<OfferingVesting>,
+    },
+    ApprovalOffering {
+        is_approved: bool,
+        reason_hash: Option<[u8; 32]>,
+    },
+    CloseOffering {
+        mode: u8,
+    },
+    InitiatePurchase {
+        mode: u8,
+        amount: u64,
+    },
+    RefundPurchase {
+        mode: u8,
+    },
+    Distribute {
+        mode: u8,
+    },
+    InitiatePublisherToken {
+        mode: u8,
+        state: u8,
+        offer_creation_price: u64,
+    },
+    UpdatePublisherToken {
+        state: u8,
+        offer_creation_price: u64,
+    },
+    FinalizeOfferingCreation {
+        mode: u8,
+    },
+    UpdateOfferingTarget {
+        target: OfferingTarget,
+    },
+    DepositMarketPool {
+        mode: u8,
+    },
+    MigrateAccount,
+    ClosePublisherAccount,
+    ClosePublisherTokenAccount,
+    CloseOfferingAccount,
+    ClosePurchaseAccount,
+}
+
+/// Funding caps and schedule of an offering, shared by its creation and
+/// its pre-open updates.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct OfferingTarget {
+    pub softcap: u64,
+    pub hardcap: u64,
+    pub min_funding: u64,
+    pub max_funding: u64,
+    pub publish_time: i64,
+    pub open_time: i64,
+    pub close_time: i64,
+    pub exit_time: i64,
+}
+
+impl ContractInstruction {
+    pub fn unpack(input: &[u8]) -> Result<Self, ProgramError> {
+        let (tag, rest) = input.split_first().ok_or(INVALID_TAG_ERROR)?;
+
+        let (instruction, rest) = match *tag {
+            INITIATE_PUBLISHER_TAG => {
+                let (mode, rest) = unpack_u8(rest)?;
+                let (publisher_seed, rest) = unpack_pubkey(rest)?;
+                (
+                    Self::InitiatePublisher {
+                        mode,
+                        publisher_seed,
+                    },
+                    rest,
+                )
+            }
+            UPDATE_PUBLISHER_TAG => {
+                let (mode, rest) = unpack_u8(rest)?;
+                let (is_open_for_offering_creation, rest) = unpack_bool(rest)?;
+                (
+                    Self::UpdatePublisher {
+                        mode,
+                        is_open_for_offering_creation,
+                    },
+                    rest,
+                )
+            }
+            CLAIM_PUBLISHER_AUTHORITY_TAG => (Self::ClaimPublisherAuthority, rest),
+            WITHDRAW_PUBLISHER_TAG => {
+                let (amount, rest) = unpack_u64(rest)?;
+                (Self::WithdrawPublisher { amount }, rest)
+            }
+            WITHDRAW_PUBLISHER_TOKEN_TAG => {
+                let (amount, rest) = unpack_u64(rest)?;
+                (Self::WithdrawPublisherToken { amount }, rest)
+            }
+            INITIATE_OFFERING_TAG => {
+                let (mode, rest) = unpack_u8(rest)?;
+                // native SOL can not be offered, see ContractInstruction::pack
+                if mode & INIT_OFFERING_MODE_OFFERED_AND_SYSTEM_ARE_THE_SAME != 0 {
+                    return Err(OFFERING_OFFERED_MINT_IS_INVALID_ERROR);
+                }
+                let (offering_seed, rest) = unpack_pubkey(rest)?;
+                let (target, rest) = unpack_offering_target(rest)?;
+                let (offer_quantity, rest) = unpack_u64(rest)?;
+                let (market_pool_percentage, rest) = unpack_u8(rest)?;
+                let (metadata_cid_len, rest) = unpack_u64(rest)?;
+                let (metadata_cid, rest) = unpack_bytes(rest, metadata_cid_len)?;
+                let (num_of_vestings, mut rest) = unpack_u8(rest)?;
+
+                let mut vestings = Vec::with_capacity(num_of_vestings as usize);
+                for _ in 0..num_of_vestings {
+                    let (release_time, vesting_rest) = unpack_i64(rest)?;
+                    let (percentage, vesting_rest) = unpack_u8(vesting_rest)?;
+                    vestings.push(OfferingVesting {
+                        release_time,
+                        percentage,
+                    });
+                    rest = vesting_rest;
+                }
+
+                (
+                    Self::InitiateOffering {
+                        mode,
+                        offering_seed,
+                        target,
+                        offer_quantity,
+                        market_pool_percentage,
+                        metadata_cid: metadata_cid.to_vec(),
+                        vestings,
+                    },
+                    rest,
+                )
+            }
+            APPROVAL_OFFERING_TAG => {
+                let (is_approved, rest) = unpack_bool(rest)?;
+                let (has_reason_hash, rest) = unpack_bool(rest)?;
+                let (reason_hash, rest) = if has_reason_hash {
+                    let (reason_hash, rest) = unpack_bytes(rest, 32)?;
+                    (Some(reason_hash.try_into().unwrap()), rest)
+                } else {
+                    (None, rest)
+                };
+                (
+                    Self::ApprovalOffering {
+                        is_approved,
+                        reason_hash,
+                    },
+                    rest,
+                )
+            }
+            CLOSE_OFFERING_TAG => {
+                let (mode, rest) = unpack_u8(rest)?;
+                (Self::CloseOffering { mode }, rest)
+            }
+            INITIATE_PURCHASE_TAG => {
+                let (mode, rest) = unpack_u8(rest)?;
+                let (amount, rest) = unpack_u64(rest)?;
+                (Self::InitiatePurchase { mode, amount }, rest)
+            }
+            REFUND_PURCHASE_TAG => {
+                let (mode, rest) = unpack_u8(rest)?;
+                (Self::RefundPurchase { mode }, rest)
+            }
+            DISTRIBUTE_TAG => {
+                let (mode, rest) = unpack_u8(rest)?;
+                (Self::Distribute { mode }, rest)
+            }
+            INITITATE_PUBLISHER_TOKEN_TAG => {
+                let (mode, rest) = unpack_u8(rest)?;
+                let (state, rest) = unpack_u8(rest)?;
+                let (offer_creation_price, rest) = unpack_u64(rest)?;
+                (
+                    Self::InitiatePublisherToken {
+                        mode,
+                        state,
+                        offer_creation_price,
+                    },
+                    rest,
+                )
+            }
+            UPDATE_PUBLISHER_TOKEN_TAG => {
+                let (state, rest) = unpack_u8(rest)?;
+                let (offer_creation_price, rest) = unpack_u64(rest)?;
+                (
+                    Self::UpdatePublisherToken {
+                        state,
+                        offer_creation_price,
+                    },
+                    rest,
+                )
+            }
+            FINALIZE_OFFERING_CREATION_TAG => {
+                let (mode, rest) = unpack_u8(rest)?;
+                (Self::FinalizeOfferingCreation { mode }, rest)
+            }
+            UPDATE_OFFERING_TARGET_TAG => {
+                let (target, rest) = unpack_offering_target(rest)?;
+                (Self::UpdateOfferingTarget { target }, rest)
+            }
+            DEPOSIT_MARKET_POOL_TAG => {
+                let (mode, rest) = unpack_u8(rest)?;
+                (Self::DepositMarketPool { mode }, rest)
+            }
+            MIGRATE_ACCOUNT_TAG => (Self::MigrateAccount, rest),
+            CLOSE_PUBLISHER_ACCOUNT_TAG => (Self::ClosePublisherAccount, rest),
+            CLOSE_PUBLISHER_TOKEN_ACCOUNT_TAG => (Self::ClosePublisherTokenAccount, rest),
+            CLOSE_OFFERING_ACCOUNT_TAG => (Self::CloseOfferingAccount, rest),
+            CLOSE_PURCHASE_ACCOUNT_TAG => (Self::ClosePurchaseAccount, rest),
+            _ => return Err(INVALID_TAG_ERROR),
+        };
+
+        if !rest.is_empty() {
+            return Err(INVALID_INSTRUCTION_ERROR);
+        }
+
+        Ok(instruction)
+    }
+
+    /// Fails with `INVALID_NUM_OF_VESTINGS_ERROR` when an `InitiateOffering`
+    /// carries more than `MAX_NUM_OF_VESTINGS` vestings, the program would
+    /// reject it and the count is packed in one byte. Fails with
+    /// `OFFERING_OFFERED_MINT_IS_INVALID_ERROR` when its mode claims native SOL
+    /// is offered, offered tokens are escrowed in token accounts.
+    pub fn pack(&self) -> Result<Vec<u8>, ProgramError> {
+        let mut buf = Vec::new();
+
+        match self {
+            Self::InitiatePublisher {
+                mode,
+                publisher_seed,
+            } => {
+                buf.push(INITIATE_PUBLISHER_TAG);
+                buf.push(*mode);
+                buf.extend_from_slice(publisher_seed.as_ref());
+            }
+            Self::UpdatePublisher {
+                mode,
+                is_open_for_offering_creation,
+            } => {
+                buf.push(UPDATE_PUBLISHER_TAG);
+                buf.push(*mode);
+                buf.push(*is_open_for_offering_creation as u8);
+            }
+            Self::ClaimPublisherAuthority => {
+                buf.push(CLAIM_PUBLISHER_AUTHORITY_TAG);
+            }
+            Self::WithdrawPublisher { amount } => {
+                buf.push(WITHDRAW_PUBLISHER_TAG);
+                buf.extend_from_slice(&amount.to_le_bytes());
+            }
+            Self::WithdrawPublisherToken { amount } => {
+                buf.push(WITHDRAW_PUBLISHER_TOKEN_TAG);
+                buf.extend_from_slice(&amount.to_le_bytes());
+            }
+            Self::InitiateOffering {
+                mode,
+                offering_seed,
+                target,
+                offer_quantity,
+                market_pool_percentage,
+                metadata_cid,
+                vestings,
+            } => {
+                if mode & INIT_OFFERING_MODE_OFFERED_AND_SYSTEM_ARE_THE_SAME != 0 {
+                    return Err(OFFERING_OFFERED_MINT_IS_INVALID_ERROR);
+                }
+                buf.push(INITIATE_OFFERING_TAG);
+                buf.push(*mode);
+                buf.extend_from_slice(offering_seed.as_ref());
+                pack_offering_target(&mut buf, target);
+                buf.extend_from_slice(&offer_quantity.to_le_bytes());
+                buf.push(*market_pool_percentage);
+                buf.extend_from_slice(&(metadata_cid.len() as u64).to_le_bytes());
+                buf.extend_from_slice(metadata_cid);
+                if vestings.len() > MAX_NUM_OF_VESTINGS {
+                    return Err(INVALID_NUM_OF_VESTINGS_ERROR);
+                }
+                buf.push(vestings.len() as u8);
+                for vesting in vestings.iter() {
+                    buf.extend_from_slice(&vesting.release_time.to_le_bytes());
+                    buf.push(vesting.percentage);
+                }
+            }
+            Self::ApprovalOffering {
+                is_approved,
+                reason_hash,
+            } => {
+                buf.push(APPROVAL_OFFERING_TAG);
+                buf.push(*is_approved as u8);
+                match reason_hash {
+                    Some(reason_hash) => {
+                        buf.push(1);
+                        buf.extend_from_slice(reason_hash);
+                    }
+                    None => buf.push(0),
+                }
+            }
+            Self::CloseOffering { mode } => {
+                buf.push(CLOSE_OFFERING_TAG);
+                buf.push(*mode);
+            }
+            Self::InitiatePurchase { mode, amount } => {
+                buf.push(INITIATE_PURCHASE_TAG);
+                buf.push(*mode);
+                buf.extend_from_slice(&amount.to_le_bytes());
+            }
+            Self::RefundPurchase { mode } => {
+                buf.push(REFUND_PURCHASE_TAG);
+                buf.push(*mode);
+            }
+            Self::Distribute { mode } => {
+                buf.push(DISTRIBUTE_TAG);
+                buf.push(*mode);
+            }
+            Self::InitiatePublisherToken {
+                mode,
+                state,
+                offer_creation_price,
+            } => {
+                buf.push(INITITATE_PUBLISHER_TOKEN_TAG);
+                buf.push(*mode);
+                buf.push(*state);
+                buf.extend_from_slice(&offer_creation_price.to_le_bytes());
+            }
+            Self::UpdatePublisherToken {
+                state,
+                offer_creation_price,
+            } => {
+                buf.push(UPDATE_PUBLISHER_TOKEN_TAG);
+                buf.push(*state);
+                buf.extend_from_slice(&offer_creation_price.to_le_bytes());
+            }
+            Self::FinalizeOfferingCreation { mode } => {
+                buf.push(FINALIZE_OFFERING_CREATION_TAG);
+                buf.push(*mode);
+            }
+            Self::UpdateOfferingTarget { target } => {
+                buf.push(UPDATE_OFFERING_TARGET_TAG);
+                pack_offering_target(&mut buf, target);
+            }
+            Self::DepositMarketPool { mode } => {
+                buf.push(DEPOSIT_MARKET_POOL_TAG);
+                buf.push(*mode);
+            }
+            Self::MigrateAccount => {
+                buf.push(MIGRATE_ACCOUNT_TAG);
+            }
+            Self::ClosePublisherAccount => {
+                buf.push(CLOSE_PUBLISHER_ACCOUNT_TAG);
+            }
+            Self::ClosePublisherTokenAccount => {
+                buf.push(CLOSE_PUBLISHER_TOKEN_ACCOUNT_TAG);
+            }
+            Self::CloseOfferingAccount => {
+                buf.push(CLOSE_OFFERING_ACCOUNT_TAG);
+            }
+            Self::ClosePurchaseAccount => {
+                buf.push(CLOSE_PURCHASE_ACCOUNT_TAG);
+            }
+        }
+
+        Ok(buf)
+    }
+}
+
+/// Creates an `InitiatePublisher` instruction.
+///
+/// Accounts expected:
+///   0. `[writable, signer]` payer, becomes the publisher creator
+///   1. `[]` update authority, omitted when it is the payer
+///   2. `[writable]` publisher PDA
+///   3. `[]` system program
+pub fn initiate_publisher(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    authority: Option<Pubkey>,
+    publisher_seed: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let (publisher, _) = find_publisher_address(program_id, payer, publisher_seed);
+
+    let mut mode = 0;
+    let mut accounts = vec![AccountMeta::new(*payer, true)];
+    match authority {
+        Some(authority) if authority.ne(payer) => {
+            accounts.push(AccountMeta::new_readonly(authority, false));
+        }
+        _ => mode |= INIT_PUBLISHER_MODE_PAYER_AND_AUTHORITY_ARE_THE_SAME,
+    }
+    accounts.push(AccountMeta::new(publisher, false));
+    accounts.push(AccountMeta::new_readonly(system_program::id(), false));
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data: ContractInstruction::InitiatePublisher {
+            mode,
+            publisher_seed: *publisher_seed,
+        }
+        .pack()?,
+    })
+}
+
+/// Creates an `UpdatePublisher` instruction. An authority left as `None` is
+/// kept as it is. Passing the update authority as `transfer_authority`
+/// cancels any pending authority nomination.
+///
+/// Accounts expected:
+///   0. `[writable, signer]` payer
+///   1. `[signer]` update authority, omitted when it is the payer
+///   2. `[]` nominated transfer authority, omitted when it is kept or is the update authority
+///   3. `[]` approval authority, omitted when it is kept or is the new transfer authority
+///   4. `[writable]` publisher PDA
+pub fn update_publisher(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    authority: &Pubkey,
+    publisher: &Pubkey,
+    transfer_authority: Option<Pubkey>,
+    approval_authority: Option<Pubkey>,
+    is_open_for_offering_creation: bool,
+) -> Result<Instruction, ProgramError> {
+    let mut mode = 0;
+    let mut accounts = vec![AccountMeta::new(*payer, true)];
+    if authority.ne(payer) {
+        accounts.push(AccountMeta::new_readonly(*authority, true));
+    } else {
+        mode |= UPDATE_PUBLISHER_MODE_PAYER_AND_AUTHORITY_ARE_THE_SAME;
+    }
+    if let Some(transfer_authority) = transfer_authority {
+        mode |= UPDATE_PUBLISHER_MODE_SET_TRANSFER_AUTHORITY;
+        if transfer_authority.ne(authority) {
+            accounts.push(AccountMeta::new_readonly(transfer_authority, false));
+        } else {
+            mode |= UPDATE_PUBLISHER_MODE_AUTHORITY_AND_TRANSFER_ARE_THE_SAME;
+        }
+    }
+    if let Some(approval_authority) = approval_authority {
+        mode |= UPDATE_PUBLISHER_MODE_SET_APPROVAL_AUTHORITY;
+        if transfer_authority
+            .is_some_and(|transfer_authority| transfer_authority.eq(&approval_authority))
+        {
+            mode |= UPDATE_PUBLISHER_MODE_TRANSFER_AND_APPROVAL_ARE_THE_SAME;
+        } else {
+            accounts.push(AccountMeta::new_readonly(approval_authority, false));
+        }
+    }
+    accounts.push(AccountMeta::new(*publisher, false));
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data: ContractInstruction::UpdatePublisher {
+            mode,
+            is_open_for_offering_creation,
+        }
+        .pack()?,
+    })
+}
+
+/// Creates a `ClaimPublisherAuthority` instruction.
+///
+/// Accounts expected:
+///   0. `[signer]` nominated transfer authority
+///   1. `[writable]` publisher PDA
+pub fn claim_publisher_authority(
+    program_id: &Pubkey,
+    new_authority: &Pubkey,
+    publisher: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*new_authority, true),
+            AccountMeta::new(*publisher, false),
+        ],
+        data: ContractInstruction::ClaimPublisherAuthority.pack()?,
+    })
+}
+
+/// Creates a `ClaimPublisherAuthority` instruction signed by the current
+/// update authority, cancelling a pending authority nomination.
+///
+/// Accounts expected:
+///   0. `[signer]` current update authority
+///   1. `[writable]` publisher PDA
+pub fn cancel_publisher_authority_transfer(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    publisher: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    claim_publisher_authority(program_id, authority, publisher)
+}
+
+/// Creates a `WithdrawPublisher` instruction moving SOL out of the publisher
+/// custodial PDA. An `amount` of zero withdraws the whole balance.
+///
+/// Accounts expected:
+///   0. `[writable, signer]` update authority, receives the lamports
+///   1. `[]` publisher PDA
+///   2. `[writable]` publisher custodial PDA
+///   3. `[]` system program
+pub fn withdraw_publisher(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    publisher: &Pubkey,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let (custodial, _) = find_custodial_address(program_id, publisher);
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*authority, true),
+            AccountMeta::new_readonly(*publisher, false),
+            AccountMeta::new(custodial, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: ContractInstruction::WithdrawPublisher { amount }.pack()?,
+    })
+}
+
+/// Creates a `WithdrawPublisherToken` instruction moving tokens out of the
+/// publisher custodial ATA. An `amount` of zero withdraws the whole balance.
+///
+/// Accounts expected:
+///   0. `[signer]` update authority
+///   1. `[]` publisher PDA
+///   2. `[]` publisher custodial PDA
+///   3. `[]` mint
+///   4. `[writable]` custodial ATA
+///   5. `[writable]` update authority ATA
+///   6. `[]` token program
+pub fn withdraw_publisher_token(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    publisher: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let (custodial, _) = find_custodial_address(program_id, publisher);
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new_readonly(*publisher, false),
+            AccountMeta::new_readonly(custodial, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new(
+                get_associated_token_address_with_program_id(&custodial, mint, token_program),
+                false,
+            ),
+            AccountMeta::new(
+                get_associated_token_address_with_program_id(authority, mint, token_program),
+                false,
+            ),
+            AccountMeta::new_readonly(*token_program, false),
+        ],
+        data: ContractInstruction::WithdrawPublisherToken { amount }.pack()?,
+    })
+}
+
+/// Creates an `InitiateOffering` instruction. The promoter pays the offer
+/// creation price of `payment_mint` to the publisher custodial PDA, a payment
+/// mint equal to the system program id means the price is paid in SOL.
+///
+/// Accounts expected:
+///   0. `[writable, signer]` payer, becomes the offering creator
+///   1. `[writable, signer]` promoter, omitted when it is the payer
+///   2. `[writable]` publisher PDA
+///   3. `[]` publisher token PDA of the funded mint
+///   4. `[]` publisher token PDA of the offered mint
+///   5. `[writable]` offering PDA
+///   6. `[]` system program
+///   7. `[]` publisher token PDA of the payment mint
+///   8. `[writable]` publisher custodial PDA
+///   9. `[]` payment mint, omitted when paid in SOL
+///  10. `[writable]` promoter ATA of the payment mint, omitted when paid in SOL
+///  11. `[writable]` custodial ATA of the payment mint, omitted when paid in SOL
+///  12. `[]` payment token program, omitted when paid in SOL
+///  13. `[]` funded mint, omitted when funded in SOL
+///  14. `[writable]` offering funds ATA, created when missing, omitted when funded in SOL
+///  15. `[]` funded token program, omitted when funded in SOL
+///  16. `[]` associated token program, omitted when funded in SOL
+#[allow(clippy::too_many_arguments)]
+pub fn initiate_offering(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    promoter: Option<Pubkey>,
+    publisher: &Pubkey,
+    funded_mint: &Pubkey,
+    funded_token_program: &Pubkey,
+    offered_mint: &Pubkey,
+    payment_mint: &Pubkey,
+    payment_token_program: &Pubkey,
+    offering_seed: &Pubkey,
+    target: OfferingTarget,
+    offer_quantity: u64,
+    market_pool_percentage: u8,
+    metadata_cid: Vec<u8>,
+    vestings: Vec<OfferingVesting>,
+) -> Result<Instruction, ProgramError> {
+    let promoter = promoter.unwrap_or(*payer);
+    let (funded_publisher_token, _) =
+        find_publisher_token_address(program_id, publisher, funded_mint);
+    let (offered_publisher_token, _) =
+        find_publisher_token_address(program_id, publisher, offered_mint);
+    let (payment_publisher_token, _) =
+        find_publisher_token_address(program_id, publisher, payment_mint);
+    let (offering, _) = find_offering_address(program_id, publisher, payer, offering_seed);
+    let (custodial, _) = find_custodial_address(program_id, publisher);
+
+    let mut mode = 0;
+    let mut accounts = vec![AccountMeta::new(*payer, true)];
+    if promoter.ne(payer) {
+        accounts.push(AccountMeta::new(promoter, true));
+    } else {
+        mode |= INIT_OFFERING_MODE_PAYER_AND_PROMOTER_ARE_THE_SAME;
+    }
+    if offered_mint.eq(&system_program::id()) {
+        return Err(OFFERING_OFFERED_MINT_IS_INVALID_ERROR);
+    }
+    accounts.push(AccountMeta::new(*publisher, false));
+    accounts.push(AccountMeta::new_readonly(funded_publisher_token, false));
+    accounts.push(AccountMeta::new_readonly(offered_publisher_token, false));
+    accounts.push(AccountMeta::new(offering, false));
+    accounts.push(AccountMeta::new_readonly(system_program::id(), false));
+    accounts.push(AccountMeta::new_readonly(payment_publisher_token, false));
+    accounts.push(AccountMeta::new(custodial, false));
+    if payment_mint.ne(&system_program::id()) {
+        accounts.push(AccountMeta::new_readonly(*payment_mint, false));
+        accounts.push(AccountMeta::new(
+            get_associated_token_address_with_program_id(
+                &promoter,
+                payment_mint,
+                payment_token_program,
+            ),
+            false,
+        ));
+        accounts.push(AccountMeta::new(
+            get_associated_token_address_with_program_id(
+                &custodial,
+                payment_mint,
+                payment_token_program,
+            ),
+            false,
+        ));
+        accounts.push(AccountMeta::new_readonly(*payment_token_program, false));
+    } else {
+        mode |= INIT_OFFERING_MODE_PAYMENT_AND_SYSTEM_ARE_THE_SAME;
+    }
+    if funded_mint.ne(&system_program::id()) {
+        accounts.push(AccountMeta::new_readonly(*funded_mint, false));
+        accounts.push(AccountMeta::new(
+            get_associated_token_address_with_program_id(
+                &offering,
+                funded_mint,
+                funded_token_program,
+            ),
+            false,
+        ));
+        accounts.push(AccountMeta::new_readonly(*funded_token_program, false));
+        accounts.push(AccountMeta::new_readonly(
+            spl_associated_token_account::id(),
+            false,
+        ));
+    } else {
+        mode |= INIT_OFFERING_MODE_FUNDED_AND_SYSTEM_ARE_THE_SAME;
+    }
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data: ContractInstruction::InitiateOffering {
+            mode,
+            offering_seed: *offering_seed,
+            target,
+            offer_quantity,
+            market_pool_percentage,
+            metadata_cid,
+            vestings,
+        }
+        .pack()?,
+    })
+}
+
+/// Creates an `ApprovalOffering` instruction.
+///
+/// Accounts expected:
+///   0. `[signer]` publisher approval authority
+///   1. `[]` publisher PDA
+///   2. `[writable]` offering PDA
+pub fn approval_offering(
+    program_id: &Pubkey,
+    approval_authority: &Pubkey,
+    publisher: &Pubkey,
+    offering: &Pubkey,
+    is_approved: bool,
+    reason_hash: Option<[u8; 32]>,
+) -> Result<Instruction, ProgramError> {
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*approval_authority, true),
+            AccountMeta::new_readonly(*publisher, false),
+            AccountMeta::new(*offering, false),
+        ],
+        data: ContractInstruction::ApprovalOffering {
+            is_approved,
+            reason_hash,
+        }
+        .pack()?,
+    })
+}
+
+/// Creates a `CloseOffering` instruction. A funded mint equal to the system
+/// program id means the offering is funded in SOL.
+///
+/// Accounts expected:
+///   0. `[writable, signer]` payer
+///   1. `[writable, signer]` promoter, omitted when it is the payer
+///   2. `[writable]` offering PDA
+///   3. `[]` offered mint
+///   4. `[writable]` offering ATA of the offered mint
+///   5. `[writable]` promoter ATA of the offered mint
+///   6. `[]` offered token program
+///   7. `[]` funded mint, omitted when funded in SOL
+///   8. `[writable]` offering ATA of the funded mint, omitted when funded in SOL
+///   9. `[writable]` promoter (benefactor) ATA of the funded mint, omitted when funded in SOL
+///  10. `[]` funded token program, omitted when funded in SOL
+#[allow(clippy::too_many_arguments)]
+pub fn close_offering(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    promoter: &Pubkey,
+    offering: &Pubkey,
+    funded_mint: &Pubkey,
+    funded_token_program: &Pubkey,
+    offered_mint: &Pubkey,
+    offered_token_program: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let mut mode = 0;
+    let mut accounts = vec![AccountMeta::new(*payer, true)];
+    if promoter.ne(payer) {
+        accounts.push(AccountMeta::new(*promoter, true));
+    } else {
+        mode |= OFFERING_MODE_PAYER_AND_PROMOTER_ARE_THE_SAME;
+    }
+    accounts.push(AccountMeta::new(*offering, false));
+    accounts.push(AccountMeta::new_readonly(*offered_mint, false));
+    accounts.push(AccountMeta::new(
+        get_associated_token_address_with_program_id(offering, offered_mint, offered_token_program),
+        false,
+    ));
+    accounts.push(AccountMeta::new(
+        get_associated_token_address_with_program_id(promoter, offered_mint, offered_token_program),
+        false,
+    ));
+    accounts.push(AccountMeta::new_readonly(*offered_token_program, false));
+    if funded_mint.ne(&system_program::id()) {
+        accounts.push(AccountMeta::new_readonly(*funded_mint, false));
+        accounts.push(AccountMeta::new(
+            get_associated_token_address_with_program_id(
+                offering,
+                funded_mint,
+                funded_token_program,
+            ),
+            false,
+        ));
+        accounts.push(AccountMeta::new(
+            get_associated_token_address_with_program_id(
+                promoter,
+                funded_mint,
+                funded_token_program,
+            ),
+            false,
+        ));
+        accounts.push(AccountMeta::new_readonly(*funded_token_program, false));
+    } else {
+        mode |= OFFERING_MODE_FUNDED_AND_SYSTEM_ARE_THE_SAME;
+    }
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data: ContractInstruction::CloseOffering { mode }.pack()?,
+    })
+}
+
+/// Creates an `InitiatePurchase` instruction. A funded mint equal to the
+/// system program id means the offering is funded in SOL.
+///
+/// Accounts expected:
+///   0. `[writable, signer]` payer, pays for the purchase PDA
+///   1. `[writable, signer]` purchaser, omitted when it is the payer
+///   2. `[writable]` offering PDA
+///   3. `[writable]` purchase PDA
+///   4. `[]` system program
+///   5. `[]` funded mint, omitted when funded in SOL
+///   6. `[writable]` purchaser ATA of the funded mint, omitted when funded in SOL
+///   7. `[writable]` offering ATA of the funded mint, omitted when funded in SOL
+///   8. `[]` funded token program, omitted when funded in SOL
+pub fn initiate_purchase(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    purchaser: Option<Pubkey>,
+    offering: &Pubkey,
+    funded_mint: &Pubkey,
+    funded_token_program: &Pubkey,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let purchaser = purchaser.unwrap_or(*payer);
+    let (purchase, _) = find_purchase_address(program_id, offering, &purchaser);
+
+    let mut mode = 0;
+    let mut accounts = vec![AccountMeta::new(*payer, true)];
+    if purchaser.ne(payer) {
+        accounts.push(AccountMeta::new(purchaser, true));
+    } else {
+        mode |= INIT_PURCHASE_MODE_PAYER_AND_SUBJECT_ARE_THE_SAME;
+    }
+    accounts.push(AccountMeta::new(*offering, false));
+    accounts.push(AccountMeta::new(purchase, false));
+    accounts.push(AccountMeta::new_readonly(system_program::id(), false));
+    if funded_mint.ne(&system_program::id()) {
+        accounts.push(AccountMeta::new_readonly(*funded_mint, false));
+        accounts.push(AccountMeta::new(
+            get_associated_token_address_with_program_id(
+                &purchaser,
+                funded_mint,
+                funded_token_program,
+            ),
+            false,
+        ));
+        accounts.push(AccountMeta::new(
+            get_associated_token_address_with_program_id(
+                offering,
+                funded_mint,
+                funded_token_program,
+            ),
+            false,
+        ));
+        accounts.push(AccountMeta::new_readonly(*funded_token_program, false));
+    } else {
+        mode |= INIT_PURCHASE_MODE_FUNDED_AND_SYSTEM_ARE_THE_SAME;
+    }
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data: ContractInstruction::InitiatePurchase { mode, amount }.pack()?,
+    })
+}
+
+/// Creates a `RefundPurchase` instruction. A funded mint equal to the system
+/// program id means the offering is funded in SOL.
+///
+/// Accounts expected:
+///   0. `[writable, signer]` payer
+///   1. `[writable]` purchaser, omitted when it is the payer
+///   2. `[writable]` offering PDA
+///   3. `[writable]` purchase PDA
+///   4. `[]` funded mint, omitted when funded in SOL
+///   5. `[writable]` offering ATA of the funded mint, omitted when funded in SOL
+///   6. `[writable]` purchaser ATA of the funded mint, omitted when funded in SOL
+///   7. `[]` funded token program, omitted when funded in SOL
+pub fn refund_purchase(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    purchaser: Option<Pubkey>,
+    offering: &Pubkey,
+    funded_mint: &Pubkey,
+    funded_token_program: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let purchaser = purchaser.unwrap_or(*payer);
+    let (purchase, _) = find_purchase_address(program_id, offering, &purchaser);
+
+    let mut mode = 0;
+    let mut accounts = vec![AccountMeta::new(*payer, true)];
+    if purchaser.ne(payer) {
+        accounts.push(AccountMeta::new(purchaser, false));
+    } else {
+        mode |= PURCHASE_MODE_PAYER_AND_SUBJECT_ARE_THE_SAME;
+    }
+    accounts.push(AccountMeta::new(*offering, false));
+    accounts.push(AccountMeta::new(purchase, false));
+    if funded_mint.ne(&system_program::id()) {
+        accounts.push(AccountMeta::new_readonly(*funded_mint, false));
+        accounts.push(AccountMeta::new(
+            get_associated_token_address_with_program_id(
+                offering,
+                funded_mint,
+                funded_token_program,
+            ),
+            false,
+        ));
+        accounts.push(AccountMeta::new(
+            get_associated_token_address_with_program_id(
+                &purchaser,
+                funded_mint,
+                funded_token_program,
+            ),
+            false,
+        ));
+        accounts.push(AccountMeta::new_readonly(*funded_token_program, false));
+    } else {
+        mode |= PURCHASE_MODE_FUNDED_AND_SYSTEM_ARE_THE_SAME;
+    }
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data: ContractInstruction::RefundPurchase { mode }.pack()?,
+    })
+}
+
+/// Creates a `Distribute` instruction releasing every elapsed vesting of a
+/// purchase.
+///
+/// Accounts expected:
+///   0. `[writable, signer]` payer
+///   1. `[]` purchaser, omitted when it is the payer
+///   2. `[writable]` offering PDA
+///   3. `[writable]` purchase PDA
+///   4. `[]` offered mint
+///   5. `[writable]` offering ATA of the offered mint
+///   6. `[writable]` purchaser ATA of the offered mint
+///   7. `[]` offered token program
+pub fn distribute(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    purchaser: Option<Pubkey>,
+    offering: &Pubkey,
+    offered_mint: &Pubkey,
+    offered_token_program: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let purchaser = purchaser.unwrap_or(*payer);
+    let (purchase, _) = find_purchase_address(program_id, offering, &purchaser);
+
+    let mut mode = 0;
+    let mut accounts = vec![AccountMeta::new(*payer, true)];
+    if purchaser.ne(payer) {
+        accounts.push(AccountMeta::new_readonly(purchaser, false));
+    } else {
+        mode |= PURCHASE_MODE_PAYER_AND_SUBJECT_ARE_THE_SAME;
+    }
+    accounts.push(AccountMeta::new(*offering, false));
+    accounts.push(AccountMeta::new(purchase, false));
+    accounts.push(AccountMeta::new_readonly(*offered_mint, false));
+    accounts.push(AccountMeta::new(
+        get_associated_token_address_with_program_id(offering, offered_mint, offered_token_program),
+        false,
+    ));
+    accounts.push(AccountMeta::new(
+        get_associated_token_address_with_program_id(
+            &purchaser,
+            offered_mint,
+            offered_token_program,
+        ),
+        false,
+    ));
+    accounts.push(AccountMeta::new_readonly(*offered_token_program, false));
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data: ContractInstruction::Distribute { mode }.pack()?,
+    })
+}
+
+/// Creates an `InitiatePublisherToken` instruction. Passing the system
+/// program id as both `mint` and `token_program` whitelists native SOL.
+///
+/// Accounts expected:
+///   0. `[writable, signer]` payer
+///   1. `[signer]` publisher update authority, omitted when it is the payer
+///   2. `[writable]` publisher PDA
+///   3. `[writable]` publisher token PDA
+///   4. `[]` mint
+///   5. `[]` system program
+///   6. `[]` token program, omitted when it is the system program
+///   7. `[]` publisher custodial PDA, omitted for native SOL
+///   8. `[writable]` custodial ATA of the mint, created when missing, omitted
+///      for native SOL
+///   9. `[]` associated token program, omitted for native SOL
+#[allow(clippy::too_many_arguments)]
+pub fn initiate_publisher_token(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    authority: Option<Pubkey>,
+    publisher: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    state: u8,
+    offer_creation_price: u64,
+) -> Result<Instruction, ProgramError> {
+    let (publisher_token, _) = find_publisher_token_address(program_id, publisher, mint);
+
+    let mut mode = 0;
+    let mut accounts = vec![AccountMeta::new(*payer, true)];
+    match authority {
+        Some(authority) if authority.ne(payer) => {
+            accounts.push(AccountMeta::new_readonly(authority, true));
+        }
+        _ => mode |= INIT_PUBLISHER_TOKEN_MODE_PAYER_AND_AUTHORITY_ARE_THE_SAME,
+    }
+    accounts.push(AccountMeta::new(*publisher, false));
+    accounts.push(AccountMeta::new(publisher_token, false));
+    accounts.push(AccountMeta::new_readonly(*mint, false));
+    accounts.push(AccountMeta::new_readonly(system_program::id(), false));
+    if token_program.ne(&system_program::id()) {
+        let (custodial, _) = find_custodial_address(program_id, publisher);
+        accounts.push(AccountMeta::new_readonly(*token_program, false));
+        accounts.push(AccountMeta::new_readonly(custodial, false));
+        accounts.push(AccountMeta::new(
+            get_associated_token_address_with_program_id(&custodial, mint, token_program),
+            false,
+        ));
+        accounts.push(AccountMeta::new_readonly(
+            spl_associated_token_account::id(),
+            false,
+        ));
+    } else {
+        mode |= INIT_PUBLISHER_TOKEN_MODE_TOKEN_PROGRAM_AND_SYSTEM_ARE_THE_SAME;
+    }
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data: ContractInstruction::InitiatePublisherToken {
+            mode,
+            state,
+            offer_creation_price,
+        }
+        .pack()?,
+    })
+}
+
+/// Creates an `UpdatePublisherToken` instruction.
+///
+/// Accounts expected:
+///   0. `[signer]` publisher update authority
+///   1. `[]` publisher PDA
+///   2. `[writable]` publisher token PDA
+pub fn update_publisher_token(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    publisher: &Pubkey,
+    mint: &Pubkey,
+    state: u8,
+    offer_creation_price: u64,
+) -> Result<Instruction, ProgramError> {
+    let (publisher_token, _) = find_publisher_token_address(program_id, publisher, mint);
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new_readonly(*publisher, false),
+            AccountMeta::new(publisher_token, false),
+        ],
+        data: ContractInstruction::UpdatePublisherToken {
+            state,
+            offer_creation_price,
+        }
+        .pack()?,
+    })
+}
+
+/// Creates a `FinalizeOfferingCreation` instruction escrowing the offered
+/// tokens.
+///
+/// Accounts expected:
+///   0. `[writable, signer]` payer
+///   1. `[signer]` promoter, omitted when it is the payer
+///   2. `[writable]` offering PDA
+///   3. `[]` offered mint
+///   4. `[writable]` promoter ATA of the offered mint
+///   5. `[writable]` offering ATA of the offered mint
+///   6. `[]` offered token program
+pub fn finalize_offering_creation(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    promoter: &Pubkey,
+    offering: &Pubkey,
+    offered_mint: &Pubkey,
+    offered_token_program: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let mut mode = 0;
+    let mut accounts = vec![AccountMeta::new(*payer, true)];
+    if promoter.ne(payer) {
+        accounts.push(AccountMeta::new_readonly(*promoter, true));
+    } else {
+        mode |= FINALIZE_OFFERING_MODE_PAYER_AND_PROMOTER_ARE_THE_SAME;
+    }
+    accounts.push(AccountMeta::new(*offering, false));
+    accounts.push(AccountMeta::new_readonly(*offered_mint, false));
+    accounts.push(AccountMeta::new(
+        get_associated_token_address_with_program_id(promoter, offered_mint, offered_token_program),
+        false,
+    ));
+    accounts.push(AccountMeta::new(
+        get_associated_token_address_with_program_id(offering, offered_mint, offered_token_program),
+        false,
+    ));
+    accounts.push(AccountMeta::new_readonly(*offered_token_program, false));
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data: ContractInstruction::FinalizeOfferingCreation { mode }.pack()?,
+    })
+}
+
+/// Creates an `UpdateOfferingTarget` instruction. It is only accepted before
+/// the offering opens and the publisher has to approve the offering again.
+///
+/// Accounts expected:
+///   0. `[signer]` promoter
+///   1. `[writable]` offering PDA
+pub fn update_offering_target(
+    program_id: &Pubkey,
+    promoter: &Pubkey,
+    offering: &Pubkey,
+    target: OfferingTarget,
+) -> Result<Instruction, ProgramError> {
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*promoter, true),
+            AccountMeta::new(*offering, false),
+        ],
+        data: ContractInstruction::UpdateOfferingTarget { target }.pack()?,
+    })
+}
+
+/// Creates a `DepositMarketPool` instruction moving the market pool share of
+/// a closed offering into `pool_program`, one of
+/// [`crate::market_pool::MARKET_POOL_PROGRAMS`]. The market pool PDA ATAs of
+/// both mints and of the LP mint have to exist, `pool_accounts` are passed
+/// through to the pool program, see [`crate::market_pool::deposit`].
+///
+/// Accounts expected:
+///   0. `[writable, signer]` payer
+///   1. `[signer]` promoter, omitted when it is the payer
+///   2. `[writable]` offering PDA
+///   3. `[]` market pool PDA of the offering
+///   4. `[]` funded mint
+///   5. `[writable]` offering ATA of the funded mint
+///   6. `[writable]` market pool PDA ATA of the funded mint
+///   7. `[]` funded token program
+///   8. `[]` offered mint
+///   9. `[writable]` offering ATA of the offered mint
+///  10. `[writable]` market pool PDA ATA of the offered mint
+///  11. `[]` offered token program
+///  12. `[]` LP mint of the pool
+///  13. `[writable]` market pool PDA ATA of the LP mint, the pool mints to it
+///  14. `[]` LP token program
+///  15. `[]` pool program
+///  16. .. pool program specific accounts
+#[allow(clippy::too_many_arguments)]
+pub fn deposit_market_pool(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    promoter: &Pubkey,
+    offering: &Pubkey,
+    funded_mint: &Pubkey,
+    funded_token_program: &Pubkey,
+    offered_mint: &Pubkey,
+    offered_token_program: &Pubkey,
+    lp_mint: &Pubkey,
+    lp_token_program: &Pubkey,
+    pool_program: &Pubkey,
+    pool_accounts: &[AccountMeta],
+) -> Result<Instruction, ProgramError> {
+    let (market_pool, _) = find_market_pool_address(program_id, offering);
+
+    let mut mode = 0;
+    let mut accounts = vec![AccountMeta::new(*payer, true)];
+    if promoter.ne(payer) {
+        accounts.push(AccountMeta::new_readonly(*promoter, true));
+    } else {
+        mode |= OFFERING_MODE_PAYER_AND_PROMOTER_ARE_THE_SAME;
+    }
+    accounts.push(AccountMeta::new(*offering, false));
+    accounts.push(AccountMeta::new_readonly(market_pool, false));
+    accounts.push(AccountMeta::new_readonly(*funded_mint, false));
+    accounts.push(AccountMeta::new(
+        get_associated_token_address_with_program_id(offering, funded_mint, funded_token_program),
+        false,
+    ));
+    accounts.push(AccountMeta::new(
+        get_associated_token_address_with_program_id(
+            &market_pool,
+            funded_mint,
+            funded_token_program,
+        ),
+        false,
+    ));
+    accounts.push(AccountMeta::new_readonly(*funded_token_program, false));
+    accounts.push(AccountMeta::new_readonly(*offered_mint, false));
+    accounts.push(AccountMeta::new(
+        get_associated_token_address_with_program_id(offering, offered_mint, offered_token_program),
+        false,
+    ));
+    accounts.push(AccountMeta::new(
+        get_associated_token_address_with_program_id(
+            &market_pool,
+            offered_mint,
+            offered_token_program,
+        ),
+        false,
+    ));
+    accounts.push(AccountMeta::new_readonly(*offered_token_program, false));
+    accounts.push(AccountMeta::new_readonly(*lp_mint, false));
+    accounts.push(AccountMeta::new(
+        get_associated_token_address_with_program_id(&market_pool, lp_mint, lp_token_program),
+        false,
+    ));
+    accounts.push(AccountMeta::new_readonly(*lp_token_program, false));
+    accounts.push(AccountMeta::new_readonly(*pool_program, false));
+    accounts.extend_from_slice(pool_accounts);
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data: ContractInstruction::DepositMarketPool { mode }.pack()?,
+    })
+}
+
+/// Creates a `MigrateAccount` instruction rewriting a publisher created before
+/// the version byte to the current layout. Anyone can send it, the payer tops
+/// up the rent needed for the grown account. The publisher tokens and offerings
+/// created before are not counted, so a migrated publisher can not be closed.
+///
+/// Accounts expected:
+///   0. `[writable, signer]` payer
+///   1. `[writable]` publisher PDA
+///   2. `[]` system program
+pub fn migrate_account(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    account: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(*account, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: ContractInstruction::MigrateAccount.pack()?,
+    })
+}
+
+/// Creates a `ClosePublisherAccount` instruction. The publisher has to be
+/// closed for offering creation and all of its publisher tokens and offerings
+/// closed, the SOL held by its custodial PDA goes to the receiver along with
+/// the rent.
+///
+/// Accounts expected:
+///   0. `[signer]` update authority
+///   1. `[writable]` publisher PDA
+///   2. `[writable]` custodial PDA
+///   3. `[writable]` receiver
+///   4. `[]` system program
+pub fn close_publisher_account(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    publisher: &Pubkey,
+    receiver: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let (custodial, _) = find_custodial_address(program_id, publisher);
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*authority, true),
+            AccountMeta::new(*publisher, false),
+            AccountMeta::new(custodial, false),
+            AccountMeta::new(*receiver, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+        ],
+        data: ContractInstruction::ClosePublisherAccount.pack()?,
+    })
+}
+
+/// Creates a `ClosePublisherTokenAccount` instruction. The publisher token has
+/// to be disabled first, and for an SPL token its revenue held by the custodial
+/// ATA withdrawn. Pass the system program id as `token_program` for native SOL.
+///
+/// Accounts expected:
+///   0. `[signer]` update authority of the publisher
+///   1. `[writable]` publisher PDA
+///   2. `[writable]` publisher token PDA
+///   3. `[writable]` receiver
+///   4. `[]` custodial PDA, omitted for native SOL
+///   5. `[]` mint, omitted for native SOL
+///   6. `[]` custodial ATA, omitted for native SOL
+///   7. `[]` token program, omitted for native SOL
+pub fn close_publisher_token_account(
+    program_id: &Pubkey,
+    authority: &Pubkey,
+    publisher: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    receiver: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let (publisher_token, _) = find_publisher_token_address(program_id, publisher, mint);
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*authority, true),
+        AccountMeta::new(*publisher, false),
+        AccountMeta::new(publisher_token, false),
+        AccountMeta::new(*receiver, false),
+    ];
+    if mint.ne(&system_program::id()) {
+        let (custodial, _) = find_custodial_address(program_id, publisher);
+        accounts.push(AccountMeta::new_readonly(custodial, false));
+        accounts.push(AccountMeta::new_readonly(*mint, false));
+        accounts.push(AccountMeta::new_readonly(
+            get_associated_token_address_with_program_id(&custodial, mint, token_program),
+            false,
+        ));
+        accounts.push(AccountMeta::new_readonly(*token_program, false));
+    }
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts,
+        data: ContractInstruction::ClosePublisherTokenAccount.pack()?,
+    })
+}
+
+/// Creates a `CloseOfferingAccount` instruction. The offering has to be closed
+/// with `CloseOffering`, its market pool share deposited and every purchase
+/// fully distributed or refunded, then closed.
+///
+/// Accounts expected:
+///   0. `[signer]` promoter
+///   1. `[writable]` offering PDA
+///   2. `[writable]` receiver
+///   3. `[writable]` publisher PDA of the offering
+pub fn close_offering_account(
+    program_id: &Pubkey,
+    promoter: &Pubkey,
+    publisher: &Pubkey,
+    offering: &Pubkey,
+    receiver: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*promoter, true),
+            AccountMeta::new(*offering, false),
+            AccountMeta::new(*receiver, false),
+            AccountMeta::new(*publisher, false),
+        ],
+        data: ContractInstruction::CloseOfferingAccount.pack()?,
+    })
+}
+
+/// Creates a `ClosePurchaseAccount` instruction for a purchase whose vestings
+/// are all distributed.
+///
+/// Accounts expected:
+///   0. `[signer]` purchaser
+///   1. `[writable]` offering PDA
+///   2. `[writable]` purchase PDA
+///   3. `[writable]` receiver
+pub fn close_purchase_account(
+    program_id: &Pubkey,
+    purchaser: &Pubkey,
+    offering: &Pubkey,
+    receiver: &Pubkey,
+) -> Result<Instruction, ProgramError> {
+    let (purchase, _) = find_purchase_address(program_id, offering, purchaser);
+
+    Ok(Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*purchaser, true),
+            AccountMeta::new(*offering, false),
+            AccountMeta::new(purchase, false),
+            AccountMeta::new(*receiver, false),
+        ],
+        data: ContractInstruction::ClosePurchaseAccount.pack()?,
+    })
+}
+
+fn pack_offering_target(buf: &mut Vec<u8>, target: &OfferingTarget) {
+    buf.extend_from_slice(&target.softcap.to_le_bytes());
+    buf.extend_from_slice(&target.hardcap.to_le_bytes());
+    buf.extend_from_slice(&target.min_funding.to_le_bytes());
+    buf.extend_from_slice(&target.max_funding.to_le_bytes());
+    buf.extend_from_slice(&target.publish_time.to_le_bytes());
+    buf.extend_from_slice(&target.open_time.to_le_bytes());
+    buf.extend_from_slice(&target.close_time.to_le_bytes());
+    buf.extend_from_slice(&target.exit_time.to_le_bytes());
+}
+
+fn unpack_offering_target(input: &[u8]) -> Result<(OfferingTarget, &[u8]), ProgramError> {
+    let (softcap, rest) = unpack_u64(input)?;
+    let (hardcap, rest) = unpack_u64(rest)?;
+    let (min_funding, rest) = unpack_u64(rest)?;
+    let (max_funding, rest) = unpack_u64(rest)?;
+    let (publish_time, rest) = unpack_i64(rest)?;
+    let (open_time, rest) = unpack_i64(rest)?;
+    let (close_time, rest) = unpack_i64(rest)?;
+    let (exit_time, rest) = unpack_i64(rest)?;
+
+    Ok((
+        OfferingTarget {
+            softcap,
+            hardcap,
+            min_funding,
+            max_funding,
+            publish_time,
+            open_time,
+            close_time,
+            exit_time,
+        },
+        rest,
+    ))
+}
+
+fn unpack_bytes(input: &[u8], len: u64) -> Result<(&[u8], &[u8]), ProgramError> {
+    let len = usize::try_from(len).map_err(|_| INVALID_INSTRUCTION_ERROR)?;
+    if input.len() < len {
+        return Err(INVALID_INSTRUCTION_ERROR);
+    }
+    Ok(input.split_at(len))
+}
+
+fn unpack_u8(input: &[u8]) -> Result<(u8, &[u8]), ProgramError> {
+    let (value, rest) = input.split_first().ok_or(INVALID_INSTRUCTION_ERROR)?;
+    Ok((*value, rest))
+}
+
+fn unpack_bool(input: &[u8]) -> Result<(bool, &[u8]), ProgramError> {
+    let (value, rest) = unpack_u8(input)?;
+    match value {
+        0 => Ok((false, rest)),
+        1 => Ok((true, rest)),
+        _ => Err(INVALID_INSTRUCTION_ERROR),
+    }
+}
+
+fn unpack_u64(input: &[u8]) -> Result<(u64, &[u8]), ProgramError> {
+    let (value, rest) = unpack_bytes(input, 8)?;
+    Ok((u64::from_le_bytes(value.try_into().unwrap()), rest))
+}
+
+fn unpack_i64(input: &[u8]) -> Result<(i64, &[u8]), ProgramError> {
+    let (value, rest) = unpack_bytes(input, 8)?;
+    Ok((i64::from_le_bytes(value.try_into().unwrap()), rest))
+}
+
+fn unpack_pubkey(input: &[u8]) -> Result<(Pubkey, &[u8]), ProgramError> {
+    let (value, rest) = unpack_bytes(input, 32)?;
+    Ok((Pubkey::new_from_array(value.try_into().unwrap()), rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn target() -> OfferingTarget {
+        OfferingTarget {
+            softcap: 1_000,
+            hardcap: 5_000,
+            min_funding: 10,
+            max_funding: 500,
+            publish_time: 100,
+            open_time: 200,
+            close_time: 300,
+            exit_time: 400,
+        }
+    }
+
+    fn instructions() -> Vec<ContractInstruction> {
+        vec![
+            ContractInstruction::InitiatePublisher {
+                mode: INIT_PUBLISHER_MODE_PAYER_AND_AUTHORITY_ARE_THE_SAME,
+                publisher_seed: Pubkey::new_unique(),
+            },
+            ContractInstruction::UpdatePublisher {
+                mode: UPDATE_PUBLISHER_MODE_TRANSFER_AND_APPROVAL_ARE_THE_SAME,
+                is_open_for_offering_creation: true,
+            },
+            ContractInstruction::ClaimPublisherAuthority,
+            ContractInstruction::WithdrawPublisher { amount: 42 },
+            ContractInstruction::WithdrawPublisherToken { amount: u64::MAX },
+            ContractInstruction::InitiateOffering {
+                mode: INIT_OFFERING_MODE_PAYER_AND_PROMOTER_ARE_THE_SAME,
+                offering_seed: Pubkey::new_unique(),
+                target: target(),
+                offer_quantity: 1_000_000,
+                market_pool_percentage: 20,
+                metadata_cid: b"bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi"
+                    .to_vec(),
+                vestings: vec![
+                    OfferingVesting {
+                        release_time: 500,
+                        percentage: 40,
+                    },
+                    OfferingVesting {
+                        release_time: 600,
+                        percentage: 60,
+                    },
+                ],
+            },
+            ContractInstruction::ApprovalOffering {
+                is_approved: false,
+                reason_hash: Some([7; 32]),
+            },
+            ContractInstruction::ApprovalOffering {
+                is_approved: true,
+                reason_hash: None,
+            },
+            ContractInstruction::CloseOffering {
+                mode: OFFERING_MODE_FUNDED_AND_SYSTEM_ARE_THE_SAME,
+            },
+            ContractInstruction::InitiatePurchase {
+                mode: INIT_PURCHASE_MODE_PAYER_AND_SUBJECT_ARE_THE_SAME,
+                amount: 250,
+            },
+            ContractInstruction::RefundPurchase {
+                mode: PURCHASE_MODE_FUNDED_AND_SYSTEM_ARE_THE_SAME,
+            },
+            ContractInstruction::Distribute {
+                mode: PURCHASE_MODE_PAYER_AND_SUBJECT_ARE_THE_SAME,
+            },
+            ContractInstruction::InitiatePublisherToken {
+                mode: INIT_PUBLISHER_TOKEN_MODE_TOKEN_PROGRAM_AND_SYSTEM_ARE_THE_SAME,
+                state: 0b00000110,
+                offer_creation_price: 1_000_000,
+            },
+            ContractInstruction::UpdatePublisherToken {
+                state: 0b00001000,
+                offer_creation_price: 0,
+            },
+            ContractInstruction::FinalizeOfferingCreation {
+                mode: FINALIZE_OFFERING_MODE_PAYER_AND_PROMOTER_ARE_THE_SAME,
+            },
+            ContractInstruction::UpdateOfferingTarget { target: target() },
+            ContractInstruction::DepositMarketPool {
+                mode: OFFERING_MODE_PAYER_AND_PROMOTER_ARE_THE_SAME,
+            },
+            ContractInstruction::MigrateAccount,
+            ContractInstruction::ClosePublisherAccount,
+            ContractInstruction::ClosePublisherTokenAccount,
+            ContractInstruction::CloseOfferingAccount,
+            ContractInstruction::ClosePurchaseAccount,
+        ]
+    }
+
+    #[test]
+    fn test_pack_unpack_roundtrip() {
+        for instruction in instructions() {
+            let packed = instruction.pack().unwrap();
+            assert_eq!(ContractInstruction::unpack(&packed), Ok(instruction));
+        }
+    }
+
+    #[test]
+    fn test_unpack_rejects_truncated_and_trailing_data() {
+        for instruction in instructions() {
+            let packed = instruction.pack().unwrap();
+
+            let mut trailing = packed.clone();
+            trailing.push(0);
+            assert_eq!(
+                ContractInstruction::unpack(&trailing),
+                Err(INVALID_INSTRUCTION_ERROR)
+            );
+
+            if packed.len() > 1 {
+                assert!(ContractInstruction::unpack(&packed[..packed.len() - 1]).is_err());
+            }
+        }
+
+        assert_eq!(ContractInstruction::unpack(&[]), Err(INVALID_TAG_ERROR));
+        assert_eq!(
+            ContractInstruction::unpack(&[u8::MAX]),
+            Err(INVALID_TAG_ERROR)
+        );
+    }
+
+    #[test]
+    fn test_pack_rejects_too_many_vestings() {
+        let instruction = ContractInstruction::InitiateOffering {
+            mode: 0,
+            offering_seed: Pubkey::new_unique(),
+            target: target(),
+            offer_quantity: 1,
+            market_pool_percentage: 0,
+            metadata_cid: vec![],
+            vestings: vec![OfferingVesting::default(); MAX_NUM_OF_VESTINGS + 1],
+        };
+        assert_eq!(instruction.pack(), Err(INVALID_NUM_OF_VESTINGS_ERROR));
+
+        // the builders hand the error to the caller instead of panicking
+        let payer = Pubkey::new_unique();
+        assert_eq!(
+            initiate_offering(
+                &crate::id(),
+                &payer,
+                None,
+                &Pubkey::new_unique(),
+                &system_program::id(),
+                &system_program::id(),
+                &Pubkey::new_unique(),
+                &system_program::id(),
+                &system_program::id(),
+                &Pubkey::new_unique(),
+                target(),
+                1,
+                0,
+                vec![],
+                vec![OfferingVesting::default(); MAX_NUM_OF_VESTINGS + 1],
+            ),
+            Err(INVALID_NUM_OF_VESTINGS_ERROR)
+        );
+    }
+
+    #[test]
+    fn test_offered_native_sol_is_rejected() {
+        let instruction = ContractInstruction::InitiateOffering {
+            mode: INIT_OFFERING_MODE_OFFERED_AND_SYSTEM_ARE_THE_SAME,
+            offering_seed: Pubkey::new_unique(),
+            target: target(),
+            offer_quantity: 1,
+            market_pool_percentage: 0,
+            metadata_cid: vec![],
+            vestings: vec![],
+        };
+        assert_eq!(
+            instruction.pack(),
+            Err(OFFERING_OFFERED_MINT_IS_INVALID_ERROR)
+        );
+
+        let mut packed = ContractInstruction::InitiateOffering {
+            mode: 0,
+            offering_seed: Pubkey::new_unique(),
+            target: target(),
+            offer_quantity: 1,
+            market_pool_percentage: 0,
+            metadata_cid: vec![],
+            vestings: vec![],
+        }
+        .pack()
+        .unwrap();
+        packed[1] = INIT_OFFERING_MODE_OFFERED_AND_SYSTEM_ARE_THE_SAME;
+        assert_eq!(
+            ContractInstruction::unpack(&packed),
+            Err(OFFERING_OFFERED_MINT_IS_INVALID_ERROR)
+        );
+    }
+}
@@ -0,0 +1,93 @@
+//! Interface a market pool program has to implement to receive the market
+//! pool share of an offering. A constant-product pool can be plugged in,
+//! directly or through an adapter program, as long as it accepts this deposit
+//! and is listed in [`MARKET_POOL_PROGRAMS`].
+//!
+//! The depositor is the market pool PDA of the offering. It only ever holds
+//! the market pool share, so the pool program never gets to sign for the
+//! offering itself. The LP tokens are minted to the market pool PDA ATA of the
+//! LP mint and stay locked there, just like whatever the pool does not take.
+
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+
+pub const MARKET_POOL_DEPOSIT_TAG: u8 = 1;
+
+/// A pool program the share can be deposited into. Its LP mint is the PDA of
+/// `lp_mint_seed` and the pool, the first pool specific account of the
+/// deposit, which tells the LP tokens of that pool apart from any other.
+pub struct MarketPoolProgram {
+    pub program_id: Pubkey,
+    pub lp_mint_seed: &'static [u8],
+}
+
+impl MarketPoolProgram {
+    pub fn find_lp_mint_address(&self, pool: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[self.lp_mint_seed, pool.as_ref()], &self.program_id).0
+    }
+}
+
+// pool programs the share can be deposited into, a promoter picking any program
+// could hand the share to one that keeps the tokens without minting LP tokens.
+// Deployed AMMs are listed once an adapter implementing the deposit is, the
+// bundled local pool only exists for the tests
+pub const MARKET_POOL_PROGRAMS: &[MarketPoolProgram] = &[
+    #[cfg(feature = "local-market-pool")]
+    MarketPoolProgram {
+        program_id: solana_program::pubkey!("9nKd1bmjRDdTtw2anVYPKqpsn1Q1YqmjQsoy1eZC8FNm"),
+        lp_mint_seed: b"lp",
+    },
+];
+
+/// Creates the deposit instruction sent to the market pool program. The data
+/// is the tag byte followed by the little-endian funded and offered amounts.
+///
+/// Accounts expected:
+///   0. `[signer]` depositor, the market pool PDA of the offering
+///   1. `[writable]` depositor ATA of the funded mint
+///   2. `[writable]` depositor ATA of the offered mint
+///   3. `[]` funded mint
+///   4. `[]` offered mint
+///   5. `[]` funded token program
+///   6. `[]` offered token program
+///   7. `[]` pool, its LP mint is derived from it
+///   8. .. accounts specific to the pool program, e.g. its vaults, its LP
+///      mint and the depositor LP token account
+#[allow(clippy::too_many_arguments)]
+pub fn deposit(
+    pool_program: &Pubkey,
+    depositor: &Pubkey,
+    depositor_funded_ata: &Pubkey,
+    depositor_offered_ata: &Pubkey,
+    funded_mint: &Pubkey,
+    offered_mint: &Pubkey,
+    funded_token_program: &Pubkey,
+    offered_token_program: &Pubkey,
+    pool_accounts: &[AccountMeta],
+    funded_amount: u64,
+    offered_amount: u64,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*depositor, true),
+        AccountMeta::new(*depositor_funded_ata, false),
+        AccountMeta::new(*depositor_offered_ata, false),
+        AccountMeta::new_readonly(*funded_mint, false),
+        AccountMeta::new_readonly(*offered_mint, false),
+        AccountMeta::new_readonly(*funded_token_program, false),
+        AccountMeta::new_readonly(*offered_token_program, false),
+    ];
+    accounts.extend_from_slice(pool_accounts);
+
+    let mut data = Vec::with_capacity(1 + 8 + 8);
+    data.push(MARKET_POOL_DEPOSIT_TAG);
+    data.extend_from_slice(&funded_amount.to_le_bytes());
+    data.extend_from_slice(&offered_amount.to_le_bytes());
+
+    Instruction {
+        program_id: *pool_program,
+        accounts,
+        data,
+    }
+}
@@ -0,0 +1,166 @@
+use solana_program::pubkey::{Pubkey, PubkeyError};
+
+use crate::constant::{
+    CUSTODIAL_PDA_SEED, MARKET_POOL_PDA_SEED, OFFERING_PDA_SEED, PUBLISHER_PDA_SEED,
+    PUBLISHER_TOKEN_PDA_SEED, PURCHASE_PDA_SEED,
+};
+
+// publisher: [PUBLISHER_PDA_SEED, creator, publisher_seed]
+pub fn find_publisher_address(
+    program_id: &Pubkey,
+    creator: &Pubkey,
+    publisher_seed: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PUBLISHER_PDA_SEED,
+            creator.as_ref(),
+            publisher_seed.as_ref(),
+        ],
+        program_id,
+    )
+}
+
+pub fn create_publisher_address(
+    program_id: &Pubkey,
+    creator: &Pubkey,
+    publisher_seed: &Pubkey,
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    Pubkey::create_program_address(
+        &[
+            PUBLISHER_PDA_SEED,
+            creator.as_ref(),
+            publisher_seed.as_ref(),
+            &[bump],
+        ],
+        program_id,
+    )
+}
+
+// publisher token: [PUBLISHER_TOKEN_PDA_SEED, publisher, mint]
+pub fn find_publisher_token_address(
+    program_id: &Pubkey,
+    publisher: &Pubkey,
+    mint: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[PUBLISHER_TOKEN_PDA_SEED, publisher.as_ref(), mint.as_ref()],
+        program_id,
+    )
+}
+
+pub fn create_publisher_token_address(
+    program_id: &Pubkey,
+    publisher: &Pubkey,
+    mint: &Pubkey,
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    Pubkey::create_program_address(
+        &[
+            PUBLISHER_TOKEN_PDA_SEED,
+            publisher.as_ref(),
+            mint.as_ref(),
+            &[bump],
+        ],
+        program_id,
+    )
+}
+
+// offering: [OFFERING_PDA_SEED, publisher, creator, offering_seed]
+pub fn find_offering_address(
+    program_id: &Pubkey,
+    publisher: &Pubkey,
+    creator: &Pubkey,
+    offering_seed: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            OFFERING_PDA_SEED,
+            publisher.as_ref(),
+            creator.as_ref(),
+            offering_seed.as_ref(),
+        ],
+        program_id,
+    )
+}
+
+pub fn create_offering_address(
+    program_id: &Pubkey,
+    publisher: &Pubkey,
+    creator: &Pubkey,
+    offering_seed: &Pubkey,
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    Pubkey::create_program_address(
+        &[
+            OFFERING_PDA_SEED,
+            publisher.as_ref(),
+            creator.as_ref(),
+            offering_seed.as_ref(),
+            &[bump],
+        ],
+        program_id,
+    )
+}
+
+// purchase: [PURCHASE_PDA_SEED, offering, purchaser]
+pub fn find_purchase_address(
+    program_id: &Pubkey,
+    offering: &Pubkey,
+    purchaser: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[PURCHASE_PDA_SEED, offering.as_ref(), purchaser.as_ref()],
+        program_id,
+    )
+}
+
+pub fn create_purchase_address(
+    program_id: &Pubkey,
+    offering: &Pubkey,
+    purchaser: &Pubkey,
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    Pubkey::create_program_address(
+        &[
+            PURCHASE_PDA_SEED,
+            offering.as_ref(),
+            purchaser.as_ref(),
+            &[bump],
+        ],
+        program_id,
+    )
+}
+
+// custodial: [CUSTODIAL_PDA_SEED, publisher]
+pub fn find_custodial_address(program_id: &Pubkey, publisher: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CUSTODIAL_PDA_SEED, publisher.as_ref()], program_id)
+}
+
+pub fn create_custodial_address(
+    program_id: &Pubkey,
+    publisher: &Pubkey,
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    Pubkey::create_program_address(
+        &[CUSTODIAL_PDA_SEED, publisher.as_ref(), &[bump]],
+        program_id,
+    )
+}
+
+// market pool: [MARKET_POOL_PDA_SEED, offering]
+pub fn find_market_pool_address(program_id: &Pubkey, offering: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[MARKET_POOL_PDA_SEED, offering.as_ref()], program_id)
+}
+
+pub fn create_market_pool_address(
+    program_id: &Pubkey,
+    offering: &Pubkey,
+    bump: u8,
+) -> Result<Pubkey, PubkeyError> {
+    Pubkey::create_program_address(
+        &[MARKET_POOL_PDA_SEED, offering.as_ref(), &[bump]],
+        program_id,
+    )
+}
@@ -0,0 +1,274 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    instruction::AccountMeta,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    constant::{
+        MARKET_POOL_PDA_SEED, OFFERING_MODE_PAYER_AND_PROMOTER_ARE_THE_SAME, OFFERING_PDA_SEED,
+    },
+    error::{
+        INVALID_ATA_ERROR, INVALID_MARKET_POOL_LP_MINT_ERROR, INVALID_MARKET_POOL_PROGRAM_ERROR,
+        INVALID_OFFERING_FUNDS_ATA_ERROR, MARKET_POOL_LP_IS_NOT_RECEIVED_ERROR,
+        OFFERING_DISTRIBUTED_QUANTITY_OVERFLOW_ERROR,
+        OFFERING_FUNDS_IS_ALREADY_DEPOSITED_TO_MARKET_POOL_ERROR,
+        OFFERING_INSUFFICIENT_FUNDS_ERROR, OFFERING_IS_STILL_PURCHASEABLE_ERROR,
+        OFFERING_MARKET_POOL_PERCENTAGE_IS_INVALID_ERROR, OFFERING_OFFERED_MINT_IS_INVALID_ERROR,
+        OFFERING_SOFTCAP_IS_NOT_REACHED_ERROR, UNAUTHORIZED_ERROR,
+    },
+    events::{emit, OfferingMarketPoolDepositedEvent},
+    market_pool::{self, MARKET_POOL_PROGRAMS},
+    pda::find_market_pool_address,
+    state::{
+        offering::OFFERING_FUNDS_IS_DEPOSITED_TO_MARKET_POOL, variable_len_pack::VariableLenPack,
+    },
+};
+
+use super::{
+    offering::{load_offering, offering_pda_bump},
+    token::{token_balance, transfer_tokens, verify_ata},
+    verify_pda,
+};
+
+pub fn deposit_market_pool(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    mode: u8,
+) -> Result<(), ProgramError> {
+    let sys_clock = Clock::get()?;
+
+    let account_info_iter = &mut accounts.iter();
+
+    let payer_info = next_account_info(account_info_iter)?;
+    let promoter_info = if mode & OFFERING_MODE_PAYER_AND_PROMOTER_ARE_THE_SAME == 0 {
+        next_account_info(account_info_iter)?
+    } else {
+        payer_info
+    };
+    let offering_pda_info = next_account_info(account_info_iter)?;
+    let market_pool_pda_info = next_account_info(account_info_iter)?;
+    let funded_mint_info = next_account_info(account_info_iter)?;
+    let offering_funded_ata_info = next_account_info(account_info_iter)?;
+    let market_pool_funded_ata_info = next_account_info(account_info_iter)?;
+    let funded_token_program_info = next_account_info(account_info_iter)?;
+    let offered_mint_info = next_account_info(account_info_iter)?;
+    let offering_offered_ata_info = next_account_info(account_info_iter)?;
+    let market_pool_offered_ata_info = next_account_info(account_info_iter)?;
+    let offered_token_program_info = next_account_info(account_info_iter)?;
+    let lp_mint_info = next_account_info(account_info_iter)?;
+    let market_pool_lp_ata_info = next_account_info(account_info_iter)?;
+    let lp_token_program_info = next_account_info(account_info_iter)?;
+    let pool_program_info = next_account_info(account_info_iter)?;
+    let pool_infos = account_info_iter.as_slice();
+
+    if !payer_info.is_signer || !promoter_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut offering = load_offering(program_id, offering_pda_info)?;
+
+    // the promoter picks the pool, the share deposited to it is fixed by the offering
+    if offering.promoter.ne(promoter_info.key) {
+        return Err(UNAUTHORIZED_ERROR);
+    }
+
+    if offering.is_deposited_to_market_pool() {
+        return Err(OFFERING_FUNDS_IS_ALREADY_DEPOSITED_TO_MARKET_POOL_ERROR);
+    }
+
+    // the share is only known once the offering is closed
+    if !offering.is_funds_withdrawn() {
+        return Err(OFFERING_IS_STILL_PURCHASEABLE_ERROR);
+    }
+
+    if offering.is_rejected() || offering.funded_amount < offering.softcap {
+        return Err(OFFERING_SOFTCAP_IS_NOT_REACHED_ERROR);
+    }
+
+    if offering.market_pool_percentage == 0 {
+        return Err(OFFERING_MARKET_POOL_PERCENTAGE_IS_INVALID_ERROR);
+    }
+
+    let market_pool_amount = offering.market_pool_amount();
+    let market_pool_quantity = offering
+        .offer_quantity_for(market_pool_amount)
+        .ok_or(OFFERING_DISTRIBUTED_QUANTITY_OVERFLOW_ERROR)?;
+
+    if market_pool_amount == 0 || market_pool_quantity == 0 {
+        return Err(OFFERING_INSUFFICIENT_FUNDS_ERROR);
+    }
+
+    let market_pool_program = MARKET_POOL_PROGRAMS
+        .iter()
+        .find(|program| program.program_id.eq(pool_program_info.key))
+        .filter(|_| pool_program_info.executable)
+        .ok_or(INVALID_MARKET_POOL_PROGRAM_ERROR)?;
+
+    let (market_pool_pda, market_pool_pda_bump) =
+        find_market_pool_address(program_id, offering_pda_info.key);
+    verify_pda(market_pool_pda_info, &market_pool_pda)?;
+
+    if offering.funded_mint.ne(funded_mint_info.key) {
+        return Err(INVALID_OFFERING_FUNDS_ATA_ERROR);
+    }
+
+    if offering.offered_mint.ne(offered_mint_info.key) {
+        return Err(OFFERING_OFFERED_MINT_IS_INVALID_ERROR);
+    }
+
+    verify_ata(
+        offering_funded_ata_info,
+        offering_pda_info.key,
+        funded_mint_info.key,
+        funded_token_program_info.key,
+        INVALID_OFFERING_FUNDS_ATA_ERROR,
+    )?;
+
+    verify_ata(
+        market_pool_funded_ata_info,
+        market_pool_pda_info.key,
+        funded_mint_info.key,
+        funded_token_program_info.key,
+        INVALID_ATA_ERROR,
+    )?;
+
+    verify_ata(
+        offering_offered_ata_info,
+        offering_pda_info.key,
+        offered_mint_info.key,
+        offered_token_program_info.key,
+        INVALID_ATA_ERROR,
+    )?;
+
+    verify_ata(
+        market_pool_offered_ata_info,
+        market_pool_pda_info.key,
+        offered_mint_info.key,
+        offered_token_program_info.key,
+        INVALID_ATA_ERROR,
+    )?;
+
+    verify_ata(
+        market_pool_lp_ata_info,
+        market_pool_pda_info.key,
+        lp_mint_info.key,
+        lp_token_program_info.key,
+        INVALID_ATA_ERROR,
+    )?;
+
+    // the LP balance below only proves the deposit when it is the LP mint of the pool receiving it
+    let pool_info = pool_infos
+        .first()
+        .ok_or(ProgramError::NotEnoughAccountKeys)?;
+    if market_pool_program
+        .find_lp_mint_address(pool_info.key)
+        .ne(lp_mint_info.key)
+    {
+        return Err(INVALID_MARKET_POOL_LP_MINT_ERROR);
+    }
+
+    let lp_balance = token_balance(market_pool_lp_ata_info, lp_token_program_info)?;
+
+    offering.state |= OFFERING_FUNDS_IS_DEPOSITED_TO_MARKET_POOL;
+    offering.pack(&mut offering_pda_info.data.borrow_mut())?;
+
+    let offering_pda_bump = offering_pda_bump(program_id, &offering, offering_pda_info)?;
+    let offering_pda_seeds: &[&[u8]] = &[
+        OFFERING_PDA_SEED,
+        offering.publisher.as_ref(),
+        offering.creator.as_ref(),
+        offering.offering_seed.as_ref(),
+        &[offering_pda_bump],
+    ];
+
+    transfer_tokens(
+        funded_token_program_info,
+        offering_funded_ata_info,
+        funded_mint_info,
+        market_pool_funded_ata_info,
+        offering_pda_info,
+        market_pool_amount,
+        &[offering_pda_seeds],
+    )?;
+
+    transfer_tokens(
+        offered_token_program_info,
+        offering_offered_ata_info,
+        offered_mint_info,
+        market_pool_offered_ata_info,
+        offering_pda_info,
+        market_pool_quantity,
+        &[offering_pda_seeds],
+    )?;
+
+    // transfer fees of Token-2022 mints may have shaved off part of the share,
+    // so the pool gets whatever the market pool PDA actually holds
+    let funded_amount = token_balance(market_pool_funded_ata_info, funded_token_program_info)?;
+    let offered_amount = token_balance(market_pool_offered_ata_info, offered_token_program_info)?;
+
+    let pool_accounts: Vec<_> = pool_infos
+        .iter()
+        .map(|info| AccountMeta {
+            pubkey: *info.key,
+            is_signer: info.is_signer,
+            is_writable: info.is_writable,
+        })
+        .collect();
+
+    let mut deposit_infos = vec![
+        market_pool_pda_info.clone(),
+        market_pool_funded_ata_info.clone(),
+        market_pool_offered_ata_info.clone(),
+        funded_mint_info.clone(),
+        offered_mint_info.clone(),
+        funded_token_program_info.clone(),
+        offered_token_program_info.clone(),
+    ];
+    deposit_infos.extend(pool_infos.iter().cloned());
+
+    invoke_signed(
+        &market_pool::deposit(
+            pool_program_info.key,
+            market_pool_pda_info.key,
+            market_pool_funded_ata_info.key,
+            market_pool_offered_ata_info.key,
+            funded_mint_info.key,
+            offered_mint_info.key,
+            funded_token_program_info.key,
+            offered_token_program_info.key,
+            &pool_accounts,
+            funded_amount,
+            offered_amount,
+        ),
+        &deposit_infos,
+        &[&[
+            MARKET_POOL_PDA_SEED,
+            offering_pda_info.key.as_ref(),
+            &[market_pool_pda_bump],
+        ]],
+    )?;
+
+    // the share is only accounted for once the LP tokens sit in the custody of the market pool PDA
+    let lp_amount = token_balance(market_pool_lp_ata_info, lp_token_program_info)?
+        .checked_sub(lp_balance)
+        .ok_or(MARKET_POOL_LP_IS_NOT_RECEIVED_ERROR)?;
+    if lp_amount == 0 {
+        return Err(MARKET_POOL_LP_IS_NOT_RECEIVED_ERROR);
+    }
+
+    emit(&OfferingMarketPoolDepositedEvent {
+        timestamp: sys_clock.unix_timestamp,
+        offering_pda: *offering_pda_info.key,
+        offering,
+        pool_program: *pool_program_info.key,
+        funded_amount,
+        offered_amount,
+        lp_mint: *lp_mint_info.key,
+        lp_amount,
+    })
+}
@@ -0,0 +1,85 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_program,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    constant::{
+        ACCOUNT_VERSION, OFFERING_PDA_TYPE, PUBLISHER_PDA_TYPE, PUBLISHER_TOKEN_PDA_TYPE,
+        PURCHASE_PDA_TYPE,
+    },
+    error::{ACCOUNT_IS_ALREADY_MIGRATED_ERROR, ACCOUNT_IS_CLOSED_ERROR, INVALID_PDA_ERROR},
+    events::{emit, AccountMigratedEvent},
+    state::publisher::{PublisherAccount, PUBLISHER_HAS_UNCOUNTED_ACCOUNTS},
+};
+
+use super::{is_closed_account, resize_pda_account};
+
+// rewrites a publisher to the current layout, nothing its authority decided changes so anyone
+// may do it
+pub fn migrate_account(program_id: &Pubkey, accounts: &[AccountInfo]) -> Result<(), ProgramError> {
+    let sys_clock = Clock::get()?;
+
+    let account_info_iter = &mut accounts.iter();
+
+    let payer_info = next_account_info(account_info_iter)?;
+    let pda_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    if !payer_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    if system_program_info.key.ne(&system_program::id()) {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    if pda_info.owner.ne(program_id) {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    if is_closed_account(pda_info) {
+        return Err(ACCOUNT_IS_CLOSED_ERROR);
+    }
+
+    let discriminator = *pda_info.data.borrow().first().ok_or(INVALID_PDA_ERROR)?;
+
+    // only the publisher has a layout older than ACCOUNT_VERSION, every other
+    // account type was introduced with the version byte already in place
+    let mut publisher = match discriminator {
+        PUBLISHER_PDA_TYPE => PublisherAccount::unpack_from_slice(&pda_info.data.borrow())?,
+        PUBLISHER_TOKEN_PDA_TYPE | OFFERING_PDA_TYPE | PURCHASE_PDA_TYPE => {
+            return Err(ACCOUNT_IS_ALREADY_MIGRATED_ERROR);
+        }
+        _ => return Err(INVALID_PDA_ERROR),
+    };
+
+    if publisher.version == ACCOUNT_VERSION {
+        return Err(ACCOUNT_IS_ALREADY_MIGRATED_ERROR);
+    }
+
+    // the counters start at zero, whatever the publisher created before is not in them
+    publisher.state |= PUBLISHER_HAS_UNCOUNTED_ACCOUNTS;
+
+    // the old layout is read before resizing, then the current one is packed
+    resize_pda_account(
+        payer_info,
+        pda_info,
+        system_program_info,
+        PublisherAccount::LEN,
+    )?;
+    PublisherAccount::pack(publisher, &mut pda_info.data.borrow_mut())?;
+
+    emit(&AccountMigratedEvent {
+        timestamp: sys_clock.unix_timestamp,
+        pda: *pda_info.key,
+        previous_version: publisher.version,
+        version: ACCOUNT_VERSION,
+        publisher,
+    })
+}
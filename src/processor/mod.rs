@@ -0,0 +1,251 @@
+use solana_program::{
+    account_info::AccountInfo,
+    log,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    constant::CLOSED_PDA_TYPE, error::INVALID_PDA_ERROR, instruction::ContractInstruction,
+};
+
+pub mod market_pool;
+pub mod migration;
+pub mod offering;
+pub mod publisher;
+pub mod publisher_token;
+pub mod purchase;
+pub mod token;
+
+pub fn process_instruction(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> Result<(), ProgramError> {
+    let instruction = ContractInstruction::unpack(instruction_data)?;
+
+    match instruction {
+        ContractInstruction::InitiatePublisher {
+            mode,
+            publisher_seed,
+        } => publisher::initiate_publisher(program_id, accounts, mode, publisher_seed),
+        ContractInstruction::UpdatePublisher {
+            mode,
+            is_open_for_offering_creation,
+        } => publisher::update_publisher(program_id, accounts, mode, is_open_for_offering_creation),
+        ContractInstruction::ClaimPublisherAuthority => {
+            publisher::claim_publisher_authority(program_id, accounts)
+        }
+        ContractInstruction::WithdrawPublisher { amount } => {
+            publisher::withdraw_publisher(program_id, accounts, amount)
+        }
+        ContractInstruction::WithdrawPublisherToken { amount } => {
+            publisher::withdraw_publisher_token(program_id, accounts, amount)
+        }
+        ContractInstruction::InitiatePublisherToken {
+            mode,
+            state,
+            offer_creation_price,
+        } => publisher_token::initiate_publisher_token(
+            program_id,
+            accounts,
+            mode,
+            state,
+            offer_creation_price,
+        ),
+        ContractInstruction::UpdatePublisherToken {
+            state,
+            offer_creation_price,
+        } => publisher_token::update_publisher_token(
+            program_id,
+            accounts,
+            state,
+            offer_creation_price,
+        ),
+        ContractInstruction::InitiateOffering {
+            mode,
+            offering_seed,
+            target,
+            offer_quantity,
+            market_pool_percentage,
+            metadata_cid,
+            vestings,
+        } => offering::initiate_offering(
+            program_id,
+            accounts,
+            mode,
+            offering_seed,
+            target,
+            offer_quantity,
+            market_pool_percentage,
+            metadata_cid,
+            vestings,
+        ),
+        ContractInstruction::ApprovalOffering {
+            is_approved,
+            reason_hash,
+        } => offering::approval_offering(program_id, accounts, is_approved, reason_hash),
+        ContractInstruction::CloseOffering { mode } => {
+            offering::close_offering(program_id, accounts, mode)
+        }
+        ContractInstruction::FinalizeOfferingCreation { mode } => {
+            offering::finalize_offering_creation(program_id, accounts, mode)
+        }
+        ContractInstruction::UpdateOfferingTarget { target } => {
+            offering::update_offering_target(program_id, accounts, target)
+        }
+        ContractInstruction::DepositMarketPool { mode } => {
+            market_pool::deposit_market_pool(program_id, accounts, mode)
+        }
+        ContractInstruction::InitiatePurchase { mode, amount } => {
+            purchase::initiate_purchase(program_id, accounts, mode, amount)
+        }
+        ContractInstruction::RefundPurchase { mode } => {
+            purchase::refund_purchase(program_id, accounts, mode)
+        }
+        ContractInstruction::Distribute { mode } => {
+            purchase::distribute(program_id, accounts, mode)
+        }
+        ContractInstruction::MigrateAccount => migration::migrate_account(program_id, accounts),
+        ContractInstruction::ClosePublisherAccount => {
+            publisher::close_publisher_account(program_id, accounts)
+        }
+        ContractInstruction::ClosePublisherTokenAccount => {
+            publisher_token::close_publisher_token_account(program_id, accounts)
+        }
+        ContractInstruction::CloseOfferingAccount => {
+            offering::close_offering_account(program_id, accounts)
+        }
+        ContractInstruction::ClosePurchaseAccount => {
+            purchase::close_purchase_account(program_id, accounts)
+        }
+    }
+}
+
+pub(crate) fn verify_pda(account: &AccountInfo, expected: &Pubkey) -> Result<(), ProgramError> {
+    if expected.ne(account.key) {
+        log::sol_log_data(&[expected.as_ref()]);
+        return Err(INVALID_PDA_ERROR);
+    }
+    Ok(())
+}
+
+// creates a rent exempt PDA owned by this program, also when someone already sent lamports to it
+pub(crate) fn create_pda_account<'a>(
+    program_id: &Pubkey,
+    payer_info: &AccountInfo<'a>,
+    pda_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+    space: usize,
+    pda_seeds: &[&[u8]],
+) -> Result<(), ProgramError> {
+    let rent_exempt = Rent::get()?.minimum_balance(space);
+
+    if pda_info.lamports() == 0 {
+        return invoke_signed(
+            &system_instruction::create_account(
+                payer_info.key,
+                pda_info.key,
+                rent_exempt,
+                space as u64,
+                program_id,
+            ),
+            &[
+                payer_info.clone(),
+                pda_info.clone(),
+                system_program_info.clone(),
+            ],
+            &[pda_seeds],
+        );
+    }
+
+    if rent_exempt > pda_info.lamports() {
+        invoke(
+            &system_instruction::transfer(
+                payer_info.key,
+                pda_info.key,
+                rent_exempt - pda_info.lamports(),
+            ),
+            &[
+                payer_info.clone(),
+                pda_info.clone(),
+                system_program_info.clone(),
+            ],
+        )?;
+    }
+
+    invoke_signed(
+        &system_instruction::allocate(pda_info.key, space as u64),
+        &[pda_info.clone(), system_program_info.clone()],
+        &[pda_seeds],
+    )?;
+
+    invoke_signed(
+        &system_instruction::assign(pda_info.key, program_id),
+        &[pda_info.clone(), system_program_info.clone()],
+        &[pda_seeds],
+    )
+}
+
+// grows or shrinks a PDA owned by this program, the payer tops up the rent when it grows
+pub(crate) fn resize_pda_account<'a>(
+    payer_info: &AccountInfo<'a>,
+    pda_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+    space: usize,
+) -> Result<(), ProgramError> {
+    let rent_exempt = Rent::get()?.minimum_balance(space);
+
+    if rent_exempt > pda_info.lamports() {
+        invoke(
+            &system_instruction::transfer(
+                payer_info.key,
+                pda_info.key,
+                rent_exempt - pda_info.lamports(),
+            ),
+            &[
+                payer_info.clone(),
+                pda_info.clone(),
+                system_program_info.clone(),
+            ],
+        )?;
+    }
+
+    pda_info.realloc(space, false)
+}
+
+// drains the lamports to the receiver and wipes the data, leaving only the closed
+// discriminator behind. That only stops a later instruction of the same transaction from
+// reviving the account by topping up its lamports, once the transaction ends the runtime
+// purges the empty account and its address may be created again from scratch
+pub(crate) fn close_pda_account(
+    pda_info: &AccountInfo,
+    receiver_info: &AccountInfo,
+) -> Result<(), ProgramError> {
+    // the lamports would stay behind and keep the account alive
+    if pda_info.key.eq(receiver_info.key) {
+        return Err(ProgramError::InvalidArgument);
+    }
+
+    token::transfer_lamports(pda_info, receiver_info, pda_info.lamports())?;
+
+    let mut pda_data = pda_info.data.borrow_mut();
+    pda_data.fill(0);
+    if pda_data.len() > 0 {
+        pda_data[0] = CLOSED_PDA_TYPE;
+    }
+
+    Ok(())
+}
+
+pub(crate) fn is_closed_account(account: &AccountInfo) -> bool {
+    let account_data = account.data.borrow();
+    if account_data.len() == 0 {
+        return false;
+    }
+    account_data[0] == CLOSED_PDA_TYPE
+}
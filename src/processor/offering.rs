@@ -0,0 +1,904 @@
+use std::cell::RefMut;
+
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack},
+    pubkey::Pubkey,
+    system_program,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    constant::{
+        ACCOUNT_VERSION, FINALIZE_OFFERING_MODE_PAYER_AND_PROMOTER_ARE_THE_SAME,
+        INIT_OFFERING_MODE_FUNDED_AND_SYSTEM_ARE_THE_SAME,
+        INIT_OFFERING_MODE_PAYER_AND_PROMOTER_ARE_THE_SAME,
+        INIT_OFFERING_MODE_PAYMENT_AND_SYSTEM_ARE_THE_SAME,
+        OFFERING_MODE_FUNDED_AND_SYSTEM_ARE_THE_SAME,
+        OFFERING_MODE_PAYER_AND_PROMOTER_ARE_THE_SAME, OFFERING_PDA_SEED, OFFERING_PDA_TYPE,
+    },
+    error::{
+        ACCOUNT_IS_CLOSED_ERROR, ACCOUNT_IS_OUTDATED_ERROR, INVALID_ATA_ERROR,
+        INVALID_INSTRUCTION_ERROR, INVALID_NUM_OF_VESTINGS_ERROR,
+        INVALID_OFFERING_BENEFACTOR_ATA_ERROR, INVALID_OFFERING_EXIT_TIME_ERROR,
+        INVALID_OFFERING_FUNDS_ATA_ERROR, INVALID_OFFERING_LEN_ERROR,
+        INVALID_OFFERING_OPEN_TIME_ERROR, INVALID_OFFERING_PAYMENT_MINT_ERROR,
+        INVALID_OFFERING_PAYMENT_PAYER_ATA_ERROR, INVALID_OFFERING_TYPE_ERROR,
+        INVALID_PUBLISHER_TOKEN_ATA_ERROR, INVALID_PUBLISHER_TYPE_ERROR,
+        INVALID_VESTING_PERCENTAGE_ERROR, INVALID_VESTING_TIME_ERROR,
+        OFFERING_CLOSE_TIME_IS_INVALID_ERROR, OFFERING_DISTRIBUTED_QUANTITY_OVERFLOW_ERROR,
+        OFFERING_FUNDS_IS_ALREADY_WITHDRAWN_ERROR,
+        OFFERING_FUNDS_IS_NOT_DEPOSITED_TO_MARKET_POOL_ERROR,
+        OFFERING_FUNDS_IS_NOT_WITHDRAWN_ERROR, OFFERING_HAS_PURCHASES_ERROR,
+        OFFERING_INSUFFICIENT_FUNDS_ERROR, OFFERING_IS_NOT_DISTRIBUTED_ERROR,
+        OFFERING_IS_READY_FOR_PURCHASE_ERROR, OFFERING_IS_REJECTED_BY_PUBLISHER_ERROR,
+        OFFERING_IS_STILL_PURCHASEABLE_ERROR, OFFERING_MARKET_POOL_PERCENTAGE_IS_INVALID_ERROR,
+        OFFERING_MIN_FUNDING_IS_INVALID_ERROR, OFFERING_OFFERED_MINT_IS_INVALID_ERROR,
+        OFFERING_OFFER_IS_ALREADY_PROVIDED_ERROR, OFFERING_PUBLISH_TIME_IS_INVALID_ERROR,
+        OFFERING_TARGET_IS_INVALID_ERROR, PUBLISHER_IS_CLOSED_FOR_OFFERING_CREATION_ERROR,
+        PUBLISHER_TOKEN_IS_DISABLED_ERROR, PUBLISHER_TOKEN_IS_NOT_ALLOWED_AS_FUNDING_ERROR,
+        PUBLISHER_TOKEN_IS_NOT_ALLOWED_AS_OFFERS_ERROR,
+        PUBLISHER_TOKEN_IS_NOT_ALLOWED_AS_OFFER_CREATION_PAYMENT_ERROR, UNAUTHORIZED_ERROR,
+    },
+    events::{
+        emit, OfferingAccountClosedEvent, OfferingApprovalEvent, OfferingClosedEvent,
+        OfferingCreationFinalizedEvent, OfferingInitiatedEvent, OfferingTargetUpdatedEvent,
+    },
+    instruction::OfferingTarget,
+    market_pool::MARKET_POOL_PROGRAMS,
+    pda::find_offering_address,
+    state::{
+        offering::{
+            OfferingAccount, OfferingAccountView, OfferingVesting, MAX_METADATA_CID_LEN,
+            MAX_NUM_OF_VESTINGS, OFFERING_FUNDS_IS_WITHDRAWN, OFFERING_IS_APPROVED_BY_PUBLISHER,
+            OFFERING_IS_INITIALIZED, OFFERING_IS_REJECTED_BY_PUBLISHER, OFFERING_OFFER_IS_PROVIDED,
+        },
+        publisher::PublisherAccount,
+        variable_len_pack::VariableLenPack,
+    },
+};
+
+use super::{
+    close_pda_account, create_pda_account, is_closed_account,
+    publisher::{load_current_publisher, load_publisher, verify_custodial},
+    publisher_token::load_publisher_token,
+    token::{
+        create_ata, token_balance, transfer_lamports, transfer_native, transfer_tokens, verify_ata,
+        verify_mint,
+    },
+    verify_pda,
+};
+
+#[allow(clippy::too_many_arguments)]
+pub fn initiate_offering(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    mode: u8,
+    offering_seed: Pubkey,
+    target: OfferingTarget,
+    offer_quantity: u64,
+    market_pool_percentage: u8,
+    metadata_cid: Vec<u8>,
+    vestings: Vec<OfferingVesting>,
+) -> Result<(), ProgramError> {
+    let sys_clock = Clock::get()?;
+
+    let account_info_iter = &mut accounts.iter();
+
+    let payer_info = next_account_info(account_info_iter)?;
+    let promoter_info = if mode & INIT_OFFERING_MODE_PAYER_AND_PROMOTER_ARE_THE_SAME == 0 {
+        next_account_info(account_info_iter)?
+    } else {
+        payer_info
+    };
+    let publisher_pda_info = next_account_info(account_info_iter)?;
+    let funded_publisher_token_pda_info = next_account_info(account_info_iter)?;
+    let offered_publisher_token_pda_info = next_account_info(account_info_iter)?;
+    let offering_pda_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+    let payment_publisher_token_pda_info = next_account_info(account_info_iter)?;
+    let custodial_pda_info = next_account_info(account_info_iter)?;
+
+    if !payer_info.is_signer || !promoter_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // the publisher counts its offerings, so it is written back
+    let mut publisher = load_current_publisher(program_id, publisher_pda_info)?;
+    if !publisher.is_open_for_offering_creation() {
+        return Err(PUBLISHER_IS_CLOSED_FOR_OFFERING_CREATION_ERROR);
+    }
+
+    let funded_publisher_token = load_publisher_token(
+        program_id,
+        publisher_pda_info.key,
+        funded_publisher_token_pda_info,
+    )?;
+    let offered_publisher_token = load_publisher_token(
+        program_id,
+        publisher_pda_info.key,
+        offered_publisher_token_pda_info,
+    )?;
+
+    if funded_publisher_token.is_disabled() || offered_publisher_token.is_disabled() {
+        return Err(PUBLISHER_TOKEN_IS_DISABLED_ERROR);
+    }
+
+    if !funded_publisher_token.is_allowed_as_funding() {
+        return Err(PUBLISHER_TOKEN_IS_NOT_ALLOWED_AS_FUNDING_ERROR);
+    }
+
+    if !offered_publisher_token.is_allowed_as_offers() {
+        return Err(PUBLISHER_TOKEN_IS_NOT_ALLOWED_AS_OFFERS_ERROR);
+    }
+
+    // the mode decides which accounts follow, so it has to agree with the mints
+    let is_funded_native = funded_publisher_token.mint.eq(&system_program::id());
+    if is_funded_native != (mode & INIT_OFFERING_MODE_FUNDED_AND_SYSTEM_ARE_THE_SAME != 0) {
+        return Err(INVALID_INSTRUCTION_ERROR);
+    }
+
+    let is_offered_native = offered_publisher_token.mint.eq(&system_program::id());
+
+    // offered tokens are escrowed and distributed through token accounts, so native SOL cannot be offered
+    if is_offered_native
+        || funded_publisher_token
+            .mint
+            .eq(&offered_publisher_token.mint)
+    {
+        return Err(OFFERING_OFFERED_MINT_IS_INVALID_ERROR);
+    }
+
+    let payment_publisher_token = load_publisher_token(
+        program_id,
+        publisher_pda_info.key,
+        payment_publisher_token_pda_info,
+    )?;
+
+    if payment_publisher_token.is_disabled() {
+        return Err(PUBLISHER_TOKEN_IS_DISABLED_ERROR);
+    }
+
+    if !payment_publisher_token.is_allowed_as_offer_creation_payment() {
+        return Err(PUBLISHER_TOKEN_IS_NOT_ALLOWED_AS_OFFER_CREATION_PAYMENT_ERROR);
+    }
+
+    let is_payment_native = payment_publisher_token.mint.eq(&system_program::id());
+    if is_payment_native != (mode & INIT_OFFERING_MODE_PAYMENT_AND_SYSTEM_ARE_THE_SAME != 0) {
+        return Err(INVALID_INSTRUCTION_ERROR);
+    }
+
+    verify_custodial(program_id, publisher_pda_info.key, custodial_pda_info)?;
+
+    // the market pool is seeded through token accounts, so offerings funded in SOL can not have one,
+    // nor can any offering while no pool program is listed to deposit the share into
+    if (is_funded_native || MARKET_POOL_PROGRAMS.is_empty()) && market_pool_percentage > 0 {
+        return Err(OFFERING_MARKET_POOL_PERCENTAGE_IS_INVALID_ERROR);
+    }
+
+    if metadata_cid.len() > MAX_METADATA_CID_LEN {
+        return Err(INVALID_OFFERING_LEN_ERROR);
+    }
+
+    let (offering_pda, offering_pda_bump) = find_offering_address(
+        program_id,
+        publisher_pda_info.key,
+        payer_info.key,
+        &offering_seed,
+    );
+    verify_pda(offering_pda_info, &offering_pda)?;
+
+    let offering = OfferingAccount {
+        discriminator: OFFERING_PDA_TYPE,
+        version: ACCOUNT_VERSION,
+        state: OFFERING_IS_INITIALIZED,
+        publisher: *publisher_pda_info.key,
+        creator: *payer_info.key,
+        offering_seed,
+        promoter: *promoter_info.key,
+        funded_mint: funded_publisher_token.mint,
+        offered_mint: offered_publisher_token.mint,
+        softcap: target.softcap,
+        hardcap: target.hardcap,
+        funded_amount: 0,
+        min_funding: target.min_funding,
+        max_funding: target.max_funding,
+        offer_quantity,
+        distributed_quantity: 0,
+        market_pool_percentage,
+        publish_time: target.publish_time,
+        open_time: target.open_time,
+        close_time: target.close_time,
+        exit_time: target.exit_time,
+        num_of_purchasers: 0,
+        num_of_open_purchases: 0,
+        metadata_cid,
+        vestings,
+    };
+
+    validate_offering(&offering, sys_clock.unix_timestamp)?;
+
+    // the publisher earns the offer creation price of the chosen payment token
+    let offer_creation_price = payment_publisher_token.offer_creation_price;
+    if is_payment_native {
+        if offer_creation_price > 0 {
+            transfer_native(
+                system_program_info,
+                promoter_info,
+                custodial_pda_info,
+                offer_creation_price,
+                &[],
+            )?;
+        }
+    } else {
+        let payment_mint_info = next_account_info(account_info_iter)?;
+        let promoter_payment_ata_info = next_account_info(account_info_iter)?;
+        let custodial_payment_ata_info = next_account_info(account_info_iter)?;
+        let payment_token_program_info = next_account_info(account_info_iter)?;
+
+        if payment_publisher_token.mint.ne(payment_mint_info.key) {
+            return Err(INVALID_OFFERING_PAYMENT_MINT_ERROR);
+        }
+
+        verify_ata(
+            promoter_payment_ata_info,
+            promoter_info.key,
+            payment_mint_info.key,
+            payment_token_program_info.key,
+            INVALID_OFFERING_PAYMENT_PAYER_ATA_ERROR,
+        )?;
+
+        verify_ata(
+            custodial_payment_ata_info,
+            custodial_pda_info.key,
+            payment_mint_info.key,
+            payment_token_program_info.key,
+            INVALID_PUBLISHER_TOKEN_ATA_ERROR,
+        )?;
+
+        if offer_creation_price > 0 {
+            transfer_tokens(
+                payment_token_program_info,
+                promoter_payment_ata_info,
+                payment_mint_info,
+                custodial_payment_ata_info,
+                promoter_info,
+                offer_creation_price,
+                &[],
+            )?;
+        }
+    }
+
+    create_pda_account(
+        program_id,
+        payer_info,
+        offering_pda_info,
+        system_program_info,
+        offering.get_packed_len(),
+        &[
+            OFFERING_PDA_SEED,
+            publisher_pda_info.key.as_ref(),
+            payer_info.key.as_ref(),
+            offering_seed.as_ref(),
+            &[offering_pda_bump],
+        ],
+    )?;
+
+    offering.pack(&mut offering_pda_info.data.borrow_mut())?;
+
+    publisher.num_of_offerings = publisher
+        .num_of_offerings
+        .checked_add(1)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    PublisherAccount::pack(publisher, &mut publisher_pda_info.data.borrow_mut())?;
+
+    // purchases pay into the offering funds ATA, SOL goes to the offering PDA itself
+    if !is_funded_native {
+        let funded_mint_info = next_account_info(account_info_iter)?;
+        let offering_funds_ata_info = next_account_info(account_info_iter)?;
+        let funded_token_program_info = next_account_info(account_info_iter)?;
+        let ata_program_info = next_account_info(account_info_iter)?;
+
+        if funded_publisher_token.mint.ne(funded_mint_info.key) {
+            return Err(INVALID_OFFERING_FUNDS_ATA_ERROR);
+        }
+        verify_mint(funded_mint_info, funded_token_program_info)?;
+
+        verify_ata(
+            offering_funds_ata_info,
+            offering_pda_info.key,
+            funded_mint_info.key,
+            funded_token_program_info.key,
+            INVALID_OFFERING_FUNDS_ATA_ERROR,
+        )?;
+
+        create_ata(
+            payer_info,
+            offering_funds_ata_info,
+            offering_pda_info,
+            funded_mint_info,
+            system_program_info,
+            funded_token_program_info,
+            ata_program_info,
+        )?;
+    }
+
+    emit(&OfferingInitiatedEvent {
+        timestamp: sys_clock.unix_timestamp,
+        offering_pda: *offering_pda_info.key,
+        offering,
+        payer: *payer_info.key,
+        promoter: *promoter_info.key,
+        payment_mint: payment_publisher_token.mint,
+        offer_creation_price,
+    })
+}
+
+pub fn finalize_offering_creation(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    mode: u8,
+) -> Result<(), ProgramError> {
+    let sys_clock = Clock::get()?;
+
+    let account_info_iter = &mut accounts.iter();
+
+    let payer_info = next_account_info(account_info_iter)?;
+    let promoter_info = if mode & FINALIZE_OFFERING_MODE_PAYER_AND_PROMOTER_ARE_THE_SAME == 0 {
+        next_account_info(account_info_iter)?
+    } else {
+        payer_info
+    };
+    let offering_pda_info = next_account_info(account_info_iter)?;
+    let offered_mint_info = next_account_info(account_info_iter)?;
+    let promoter_offered_ata_info = next_account_info(account_info_iter)?;
+    let offering_offered_ata_info = next_account_info(account_info_iter)?;
+    let offered_token_program_info = next_account_info(account_info_iter)?;
+
+    if !payer_info.is_signer || !promoter_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut offering = load_offering(program_id, offering_pda_info)?;
+
+    if offering.promoter.ne(promoter_info.key) {
+        return Err(UNAUTHORIZED_ERROR);
+    }
+
+    if offering.is_offer_provided() {
+        return Err(OFFERING_OFFER_IS_ALREADY_PROVIDED_ERROR);
+    }
+
+    if offering.is_rejected() {
+        return Err(OFFERING_IS_REJECTED_BY_PUBLISHER_ERROR);
+    }
+
+    if sys_clock.unix_timestamp >= offering.open_time {
+        return Err(OFFERING_IS_READY_FOR_PURCHASE_ERROR);
+    }
+
+    if offering.offered_mint.ne(offered_mint_info.key) {
+        return Err(OFFERING_OFFERED_MINT_IS_INVALID_ERROR);
+    }
+
+    verify_ata(
+        offering_offered_ata_info,
+        offering_pda_info.key,
+        offered_mint_info.key,
+        offered_token_program_info.key,
+        INVALID_ATA_ERROR,
+    )?;
+
+    let escrow_quantity = offering
+        .escrow_quantity()
+        .ok_or(OFFERING_TARGET_IS_INVALID_ERROR)?;
+
+    transfer_tokens(
+        offered_token_program_info,
+        promoter_offered_ata_info,
+        offered_mint_info,
+        offering_offered_ata_info,
+        promoter_info,
+        escrow_quantity,
+        &[],
+    )?;
+
+    // transfer fees of Token-2022 mints may have shaved off part of the deposit,
+    // the offering must hold every token it is going to sell or pair in the market pool
+    let escrowed_quantity = token_balance(offering_offered_ata_info, offered_token_program_info)?;
+    if escrowed_quantity < escrow_quantity {
+        return Err(OFFERING_INSUFFICIENT_FUNDS_ERROR);
+    }
+
+    offering.state |= OFFERING_OFFER_IS_PROVIDED;
+    offering.pack(&mut offering_pda_info.data.borrow_mut())?;
+
+    emit(&OfferingCreationFinalizedEvent {
+        timestamp: sys_clock.unix_timestamp,
+        offering_pda: *offering_pda_info.key,
+        offering,
+        promoter: *promoter_info.key,
+        escrow_quantity,
+    })
+}
+
+pub fn approval_offering(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    is_approved: bool,
+    reason_hash: Option<[u8; 32]>,
+) -> Result<(), ProgramError> {
+    let sys_clock = Clock::get()?;
+
+    let account_info_iter = &mut accounts.iter();
+
+    let approval_authority_info = next_account_info(account_info_iter)?;
+    let publisher_pda_info = next_account_info(account_info_iter)?;
+    let offering_pda_info = next_account_info(account_info_iter)?;
+
+    if !approval_authority_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let publisher = load_publisher(program_id, publisher_pda_info)?;
+    let mut offering = load_offering(program_id, offering_pda_info)?;
+
+    if offering.publisher.ne(publisher_pda_info.key)
+        || publisher.approval_authority.ne(approval_authority_info.key)
+    {
+        return Err(UNAUTHORIZED_ERROR);
+    }
+
+    // the decision can be revised until the offering is published, except that a
+    // rejection is final because the promoter may already have reclaimed the offer
+    if offering.is_published(sys_clock.unix_timestamp) {
+        return Err(OFFERING_PUBLISH_TIME_IS_INVALID_ERROR);
+    }
+
+    if offering.is_rejected() {
+        return Err(OFFERING_IS_REJECTED_BY_PUBLISHER_ERROR);
+    }
+
+    if is_approved {
+        offering.state |= OFFERING_IS_APPROVED_BY_PUBLISHER;
+    } else {
+        offering.state &= !OFFERING_IS_APPROVED_BY_PUBLISHER;
+        offering.state |= OFFERING_IS_REJECTED_BY_PUBLISHER;
+    }
+
+    offering.pack(&mut offering_pda_info.data.borrow_mut())?;
+
+    emit(&OfferingApprovalEvent {
+        timestamp: sys_clock.unix_timestamp,
+        offering_pda: *offering_pda_info.key,
+        offering,
+        approval_authority: *approval_authority_info.key,
+        is_approved,
+        reason_hash,
+    })
+}
+
+pub fn close_offering(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    mode: u8,
+) -> Result<(), ProgramError> {
+    let sys_clock = Clock::get()?;
+
+    let account_info_iter = &mut accounts.iter();
+
+    let payer_info = next_account_info(account_info_iter)?;
+    let promoter_info = if mode & OFFERING_MODE_PAYER_AND_PROMOTER_ARE_THE_SAME == 0 {
+        next_account_info(account_info_iter)?
+    } else {
+        payer_info
+    };
+    let offering_pda_info = next_account_info(account_info_iter)?;
+    let offered_mint_info = next_account_info(account_info_iter)?;
+    let offering_offered_ata_info = next_account_info(account_info_iter)?;
+    let promoter_offered_ata_info = next_account_info(account_info_iter)?;
+    let offered_token_program_info = next_account_info(account_info_iter)?;
+
+    if !payer_info.is_signer || !promoter_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut offering = load_offering(program_id, offering_pda_info)?;
+
+    if offering.promoter.ne(promoter_info.key) {
+        return Err(UNAUTHORIZED_ERROR);
+    }
+
+    if offering.is_funds_withdrawn() {
+        return Err(OFFERING_FUNDS_IS_ALREADY_WITHDRAWN_ERROR);
+    }
+
+    // a rejected offering never opened for purchase, any other one has to run until close_time
+    if !offering.is_rejected() && sys_clock.unix_timestamp <= offering.close_time {
+        return Err(OFFERING_IS_STILL_PURCHASEABLE_ERROR);
+    }
+
+    if offering.offered_mint.ne(offered_mint_info.key) {
+        return Err(OFFERING_OFFERED_MINT_IS_INVALID_ERROR);
+    }
+
+    verify_ata(
+        offering_offered_ata_info,
+        offering_pda_info.key,
+        offered_mint_info.key,
+        offered_token_program_info.key,
+        INVALID_ATA_ERROR,
+    )?;
+
+    verify_ata(
+        promoter_offered_ata_info,
+        promoter_info.key,
+        offered_mint_info.key,
+        offered_token_program_info.key,
+        INVALID_OFFERING_BENEFACTOR_ATA_ERROR,
+    )?;
+
+    // below the softcap every purchaser is refunded, so nothing is sold and nothing is raised.
+    // otherwise the market pool share of the funds and the offered tokens pairing with it
+    // stay in the offering until they are deposited to the market pool
+    let is_successful = !offering.is_rejected() && offering.funded_amount >= offering.softcap;
+    let (withdrawn_amount, kept_quantity) = if is_successful {
+        let market_pool_amount = offering.market_pool_amount();
+        let sold_quantity = offering
+            .offer_quantity_for(offering.funded_amount)
+            .ok_or(OFFERING_DISTRIBUTED_QUANTITY_OVERFLOW_ERROR)?;
+        let market_pool_quantity = offering
+            .offer_quantity_for(market_pool_amount)
+            .ok_or(OFFERING_DISTRIBUTED_QUANTITY_OVERFLOW_ERROR)?;
+        (
+            offering.funded_amount - market_pool_amount,
+            sold_quantity
+                .checked_add(market_pool_quantity)
+                .ok_or(OFFERING_DISTRIBUTED_QUANTITY_OVERFLOW_ERROR)?,
+        )
+    } else {
+        (0, 0)
+    };
+
+    let returned_quantity = if offering.is_offer_provided() {
+        offering
+            .escrow_quantity()
+            .and_then(|escrow_quantity| escrow_quantity.checked_sub(kept_quantity))
+            .ok_or(OFFERING_DISTRIBUTED_QUANTITY_OVERFLOW_ERROR)?
+    } else {
+        0
+    };
+
+    offering.state |= OFFERING_FUNDS_IS_WITHDRAWN;
+    offering.pack(&mut offering_pda_info.data.borrow_mut())?;
+
+    let offering_pda_bump = offering_pda_bump(program_id, &offering, offering_pda_info)?;
+    let offering_pda_seeds: &[&[u8]] = &[
+        OFFERING_PDA_SEED,
+        offering.publisher.as_ref(),
+        offering.creator.as_ref(),
+        offering.offering_seed.as_ref(),
+        &[offering_pda_bump],
+    ];
+
+    if returned_quantity > 0 {
+        transfer_tokens(
+            offered_token_program_info,
+            offering_offered_ata_info,
+            offered_mint_info,
+            promoter_offered_ata_info,
+            offering_pda_info,
+            returned_quantity,
+            &[offering_pda_seeds],
+        )?;
+    }
+
+    if withdrawn_amount > 0 {
+        if mode & OFFERING_MODE_FUNDED_AND_SYSTEM_ARE_THE_SAME == 0 {
+            let funded_mint_info = next_account_info(account_info_iter)?;
+            let offering_funds_ata_info = next_account_info(account_info_iter)?;
+            let promoter_funds_ata_info = next_account_info(account_info_iter)?;
+            let funded_token_program_info = next_account_info(account_info_iter)?;
+
+            if offering.funded_mint.ne(funded_mint_info.key) {
+                return Err(INVALID_OFFERING_FUNDS_ATA_ERROR);
+            }
+
+            verify_ata(
+                offering_funds_ata_info,
+                offering_pda_info.key,
+                funded_mint_info.key,
+                funded_token_program_info.key,
+                INVALID_OFFERING_FUNDS_ATA_ERROR,
+            )?;
+
+            verify_ata(
+                promoter_funds_ata_info,
+                promoter_info.key,
+                funded_mint_info.key,
+                funded_token_program_info.key,
+                INVALID_OFFERING_BENEFACTOR_ATA_ERROR,
+            )?;
+
+            transfer_tokens(
+                funded_token_program_info,
+                offering_funds_ata_info,
+                funded_mint_info,
+                promoter_funds_ata_info,
+                offering_pda_info,
+                withdrawn_amount,
+                &[offering_pda_seeds],
+            )?;
+        } else {
+            if offering.funded_mint.ne(&system_program::id()) {
+                return Err(INVALID_OFFERING_FUNDS_ATA_ERROR);
+            }
+
+            transfer_lamports(offering_pda_info, promoter_info, withdrawn_amount)?;
+        }
+    }
+
+    emit(&OfferingClosedEvent {
+        timestamp: sys_clock.unix_timestamp,
+        offering_pda: *offering_pda_info.key,
+        offering,
+        promoter: *promoter_info.key,
+        withdrawn_amount,
+        returned_quantity,
+    })
+}
+
+pub fn update_offering_target(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    target: OfferingTarget,
+) -> Result<(), ProgramError> {
+    let sys_clock = Clock::get()?;
+
+    let account_info_iter = &mut accounts.iter();
+
+    let promoter_info = next_account_info(account_info_iter)?;
+    let offering_pda_info = next_account_info(account_info_iter)?;
+
+    if !promoter_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut offering = load_offering(program_id, offering_pda_info)?;
+
+    if offering.promoter.ne(promoter_info.key) {
+        return Err(UNAUTHORIZED_ERROR);
+    }
+
+    if offering.is_rejected() {
+        return Err(OFFERING_IS_REJECTED_BY_PUBLISHER_ERROR);
+    }
+
+    if sys_clock.unix_timestamp >= offering.open_time {
+        return Err(OFFERING_IS_READY_FOR_PURCHASE_ERROR);
+    }
+
+    offering.softcap = target.softcap;
+    offering.hardcap = target.hardcap;
+    offering.min_funding = target.min_funding;
+    offering.max_funding = target.max_funding;
+    offering.publish_time = target.publish_time;
+    offering.open_time = target.open_time;
+    offering.close_time = target.close_time;
+    offering.exit_time = target.exit_time;
+
+    validate_offering(&offering, sys_clock.unix_timestamp)?;
+
+    // the publisher approved the previous target, the new one has to be approved again
+    offering.state &= !OFFERING_IS_APPROVED_BY_PUBLISHER;
+
+    offering.pack(&mut offering_pda_info.data.borrow_mut())?;
+
+    emit(&OfferingTargetUpdatedEvent {
+        timestamp: sys_clock.unix_timestamp,
+        offering_pda: *offering_pda_info.key,
+        offering,
+        promoter: *promoter_info.key,
+    })
+}
+
+// checks the target, market pool share and vesting schedule of an offering that is not yet published
+pub(crate) fn validate_offering(offering: &OfferingAccount, time: i64) -> Result<(), ProgramError> {
+    if offering.publish_time <= time {
+        return Err(OFFERING_PUBLISH_TIME_IS_INVALID_ERROR);
+    }
+
+    if offering.open_time < offering.publish_time {
+        return Err(INVALID_OFFERING_OPEN_TIME_ERROR);
+    }
+
+    if offering.close_time <= offering.open_time {
+        return Err(OFFERING_CLOSE_TIME_IS_INVALID_ERROR);
+    }
+
+    if offering.exit_time <= offering.close_time {
+        return Err(INVALID_OFFERING_EXIT_TIME_ERROR);
+    }
+
+    if offering.hardcap == 0 || offering.softcap > offering.hardcap || offering.offer_quantity == 0
+    {
+        return Err(OFFERING_TARGET_IS_INVALID_ERROR);
+    }
+
+    if offering.max_funding == 0
+        || offering.min_funding > offering.max_funding
+        || offering.max_funding > offering.hardcap
+    {
+        return Err(OFFERING_MIN_FUNDING_IS_INVALID_ERROR);
+    }
+
+    if offering.market_pool_percentage > 100 {
+        return Err(OFFERING_MARKET_POOL_PERCENTAGE_IS_INVALID_ERROR);
+    }
+
+    if offering.escrow_quantity().is_none() {
+        return Err(OFFERING_TARGET_IS_INVALID_ERROR);
+    }
+
+    if offering.vestings.is_empty() || offering.vestings.len() > MAX_NUM_OF_VESTINGS {
+        return Err(INVALID_NUM_OF_VESTINGS_ERROR);
+    }
+
+    let mut total_percentage: u16 = 0;
+    let mut last_release_time = offering.close_time;
+    for vesting in offering.vestings.iter() {
+        // vestings are released in order and never before the offering is closed
+        if vesting.release_time <= last_release_time {
+            return Err(INVALID_VESTING_TIME_ERROR);
+        }
+        last_release_time = vesting.release_time;
+        total_percentage += vesting.percentage as u16;
+    }
+
+    if total_percentage != 100 {
+        return Err(INVALID_VESTING_PERCENTAGE_ERROR);
+    }
+
+    Ok(())
+}
+
+pub fn close_offering_account(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> Result<(), ProgramError> {
+    let sys_clock = Clock::get()?;
+
+    let account_info_iter = &mut accounts.iter();
+
+    let promoter_info = next_account_info(account_info_iter)?;
+    let offering_pda_info = next_account_info(account_info_iter)?;
+    let receiver_info = next_account_info(account_info_iter)?;
+    let publisher_pda_info = next_account_info(account_info_iter)?;
+
+    if !promoter_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let offering = load_offering(program_id, offering_pda_info)?;
+
+    if offering.promoter.ne(promoter_info.key) {
+        return Err(UNAUTHORIZED_ERROR);
+    }
+
+    if offering.publisher.ne(publisher_pda_info.key) {
+        return Err(INVALID_PUBLISHER_TYPE_ERROR);
+    }
+    let mut publisher = load_current_publisher(program_id, publisher_pda_info)?;
+
+    // CLOSE_OFFERING has settled the funds and returned the tokens nobody bought
+    if !offering.is_funds_withdrawn() {
+        return Err(OFFERING_FUNDS_IS_NOT_WITHDRAWN_ERROR);
+    }
+
+    // every purchase is either fully distributed or refunded, so nobody needs the offering
+    // to sign for its escrow anymore
+    let is_successful = !offering.is_rejected() && offering.funded_amount >= offering.softcap;
+    if is_successful {
+        let sold_quantity = offering
+            .offer_quantity_for(offering.funded_amount)
+            .ok_or(OFFERING_IS_NOT_DISTRIBUTED_ERROR)?;
+        if offering.distributed_quantity < sold_quantity {
+            return Err(OFFERING_IS_NOT_DISTRIBUTED_ERROR);
+        }
+    }
+
+    // purchase PDAs are derived from the offering address, which a new offering with the
+    // same seed would get again, so they have to be closed first
+    if offering.num_of_open_purchases > 0 {
+        return Err(OFFERING_HAS_PURCHASES_ERROR);
+    }
+
+    let market_pool_amount = offering.market_pool_amount();
+    let market_pool_quantity = offering.offer_quantity_for(market_pool_amount).unwrap_or(0);
+    if is_successful
+        && market_pool_amount > 0
+        && market_pool_quantity > 0
+        && !offering.is_deposited_to_market_pool()
+    {
+        return Err(OFFERING_FUNDS_IS_NOT_DEPOSITED_TO_MARKET_POOL_ERROR);
+    }
+
+    close_pda_account(offering_pda_info, receiver_info)?;
+
+    publisher.num_of_offerings = publisher.num_of_offerings.saturating_sub(1);
+    PublisherAccount::pack(publisher, &mut publisher_pda_info.data.borrow_mut())?;
+
+    emit(&OfferingAccountClosedEvent {
+        timestamp: sys_clock.unix_timestamp,
+        offering_pda: *offering_pda_info.key,
+        promoter: *promoter_info.key,
+        receiver: *receiver_info.key,
+    })
+}
+
+pub(crate) fn load_offering(
+    program_id: &Pubkey,
+    offering_pda_info: &AccountInfo,
+) -> Result<OfferingAccount, ProgramError> {
+    if offering_pda_info.owner.ne(program_id) {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    if is_closed_account(offering_pda_info) {
+        return Err(ACCOUNT_IS_CLOSED_ERROR);
+    }
+
+    let offering = OfferingAccount::unpack_unchecked(&offering_pda_info.data.borrow())?;
+    if !offering.is_initialized() {
+        return Err(INVALID_OFFERING_TYPE_ERROR);
+    }
+
+    if offering.version != ACCOUNT_VERSION {
+        return Err(ACCOUNT_IS_OUTDATED_ERROR);
+    }
+
+    Ok(offering)
+}
+
+// in place access to the fixed fields of an offering, the data stays borrowed
+// while the view lives so it has to be dropped before any CPI touching the offering
+pub(crate) fn load_offering_view<'a>(
+    program_id: &Pubkey,
+    offering_pda_info: &'a AccountInfo,
+) -> Result<OfferingAccountView<RefMut<'a, [u8]>>, ProgramError> {
+    if offering_pda_info.owner.ne(program_id) {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    if is_closed_account(offering_pda_info) {
+        return Err(ACCOUNT_IS_CLOSED_ERROR);
+    }
+
+    let offering =
+        OfferingAccountView::new(RefMut::map(offering_pda_info.data.borrow_mut(), |data| {
+            &mut **data
+        }))?;
+    if offering.version() != ACCOUNT_VERSION {
+        return Err(ACCOUNT_IS_OUTDATED_ERROR);
+    }
+
+    Ok(offering)
+}
+
+// bump of the offering PDA, needed whenever the offering signs for its escrowed funds or tokens
+pub(crate) fn offering_pda_bump(
+    program_id: &Pubkey,
+    offering: &OfferingAccount,
+    offering_pda_info: &AccountInfo,
+) -> Result<u8, ProgramError> {
+    let (offering_pda, offering_pda_bump) = find_offering_address(
+        program_id,
+        &offering.publisher,
+        &offering.creator,
+        &offering.offering_seed,
+    );
+    verify_pda(offering_pda_info, &offering_pda)?;
+    Ok(offering_pda_bump)
+}
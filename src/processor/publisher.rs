@@ -0,0 +1,498 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    program::invoke_signed,
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack},
+    pubkey::Pubkey,
+    rent::Rent,
+    system_instruction, system_program,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    constant::{
+        ACCOUNT_VERSION, CUSTODIAL_PDA_SEED, INIT_PUBLISHER_MODE_PAYER_AND_AUTHORITY_ARE_THE_SAME,
+        PUBLISHER_PDA_SEED, PUBLISHER_PDA_TYPE,
+        UPDATE_PUBLISHER_MODE_AUTHORITY_AND_TRANSFER_ARE_THE_SAME,
+        UPDATE_PUBLISHER_MODE_PAYER_AND_AUTHORITY_ARE_THE_SAME,
+        UPDATE_PUBLISHER_MODE_SET_APPROVAL_AUTHORITY, UPDATE_PUBLISHER_MODE_SET_TRANSFER_AUTHORITY,
+        UPDATE_PUBLISHER_MODE_TRANSFER_AND_APPROVAL_ARE_THE_SAME,
+    },
+    error::{
+        ACCOUNT_IS_CLOSED_ERROR, ACCOUNT_IS_OUTDATED_ERROR, INVALID_INSTRUCTION_ERROR,
+        INVALID_PUBLISHER_AUTHORITY_ATA_ERROR, INVALID_PUBLISHER_TOKEN_ATA_ERROR,
+        INVALID_PUBLISHER_TYPE_ERROR, PUBLISHER_CUSTODIAL_BALANCE_IS_EMPTY_ERROR,
+        PUBLISHER_HAS_OFFERINGS_ERROR, PUBLISHER_HAS_PUBLISHER_TOKENS_ERROR,
+        PUBLISHER_HAS_UNCOUNTED_ACCOUNTS_ERROR, PUBLISHER_IS_OPEN_FOR_OFFERING_CREATION_ERROR,
+        PUBLISHER_TOKEN_BALANCE_IS_INSUFFICIENT_ERROR, TRANSFER_INSUFFICIENT_FUNDS_ERROR,
+        UNAUTHORIZED_ERROR,
+    },
+    events::{
+        emit, PublisherAccountClosedEvent, PublisherAuthorityClaimedEvent, PublisherInitiatedEvent,
+        PublisherTokenWithdrawalEvent, PublisherUpdatedEvent, PublisherWithdrawalEvent,
+    },
+    pda::{find_custodial_address, find_publisher_address},
+    state::publisher::{
+        PublisherAccount, PUBLISHER_IS_INITIALIZED, PUBLISHER_IS_OPEN_FOR_OFFERING_CREATION,
+    },
+};
+
+use super::{
+    close_pda_account, is_closed_account,
+    token::{token_balance, transfer_native, transfer_tokens, verify_ata},
+    verify_pda,
+};
+
+pub fn initiate_publisher(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    mode: u8,
+    publisher_seed: Pubkey,
+) -> Result<(), ProgramError> {
+    let sys_clock = Clock::get()?;
+    let sys_rent = Rent::get()?;
+
+    let account_info_iter = &mut accounts.iter();
+
+    let payer_info = next_account_info(account_info_iter)?;
+    let authority_info = if mode & INIT_PUBLISHER_MODE_PAYER_AND_AUTHORITY_ARE_THE_SAME == 0 {
+        next_account_info(account_info_iter)?
+    } else {
+        payer_info
+    };
+    let publisher_pda_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    if !payer_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let (publisher_pda, publisher_pda_bump) =
+        find_publisher_address(program_id, payer_info.key, &publisher_seed);
+    verify_pda(publisher_pda_info, &publisher_pda)?;
+
+    let publisher_pda_seeds: &[&[u8]] = &[
+        PUBLISHER_PDA_SEED,
+        payer_info.key.as_ref(),
+        publisher_seed.as_ref(),
+        &[publisher_pda_bump],
+    ];
+
+    let publisher_pda_rent_exempt = sys_rent.minimum_balance(PublisherAccount::LEN);
+
+    invoke_signed(
+        &system_instruction::create_account(
+            payer_info.key,
+            publisher_pda_info.key,
+            publisher_pda_rent_exempt,
+            PublisherAccount::LEN as u64,
+            program_id,
+        ),
+        &[
+            payer_info.clone(),
+            publisher_pda_info.clone(),
+            system_program_info.clone(),
+        ],
+        &[publisher_pda_seeds],
+    )?;
+
+    let publisher = PublisherAccount {
+        discriminator: PUBLISHER_PDA_TYPE,
+        version: ACCOUNT_VERSION,
+        state: PUBLISHER_IS_INITIALIZED,
+        creator: *payer_info.key,
+        publisher_seed,
+        update_authority: *authority_info.key,
+        transfer_authority: *authority_info.key,
+        approval_authority: *authority_info.key,
+        num_of_publisher_tokens: 0,
+        num_of_offerings: 0,
+    };
+
+    PublisherAccount::pack(publisher, &mut publisher_pda_info.data.borrow_mut())?;
+
+    emit(&PublisherInitiatedEvent {
+        timestamp: sys_clock.unix_timestamp,
+        publisher_pda: *publisher_pda_info.key,
+        publisher,
+        payer: *payer_info.key,
+        authority: *authority_info.key,
+    })
+}
+
+pub fn update_publisher(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    mode: u8,
+    is_open_for_offering_creation: bool,
+) -> Result<(), ProgramError> {
+    let sys_clock = Clock::get()?;
+
+    let account_info_iter = &mut accounts.iter();
+
+    let payer_info = next_account_info(account_info_iter)?;
+    let authority_info = if mode & UPDATE_PUBLISHER_MODE_PAYER_AND_AUTHORITY_ARE_THE_SAME == 0 {
+        next_account_info(account_info_iter)?
+    } else {
+        payer_info
+    };
+    // an authority is only passed, and overwritten, when its set bit is on
+    let transfer_info = if mode & UPDATE_PUBLISHER_MODE_SET_TRANSFER_AUTHORITY == 0 {
+        None
+    } else if mode & UPDATE_PUBLISHER_MODE_AUTHORITY_AND_TRANSFER_ARE_THE_SAME == 0 {
+        Some(next_account_info(account_info_iter)?)
+    } else {
+        Some(authority_info)
+    };
+    let approval_info = if mode & UPDATE_PUBLISHER_MODE_SET_APPROVAL_AUTHORITY == 0 {
+        None
+    } else if mode & UPDATE_PUBLISHER_MODE_TRANSFER_AND_APPROVAL_ARE_THE_SAME == 0 {
+        Some(next_account_info(account_info_iter)?)
+    } else {
+        Some(transfer_info.ok_or(INVALID_INSTRUCTION_ERROR)?)
+    };
+    let publisher_pda_info = next_account_info(account_info_iter)?;
+
+    if !payer_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut publisher = load_current_publisher(program_id, publisher_pda_info)?;
+
+    if !authority_info.is_signer || publisher.update_authority.ne(authority_info.key) {
+        return Err(UNAUTHORIZED_ERROR);
+    }
+
+    if let Some(transfer_info) = transfer_info {
+        publisher.transfer_authority = *transfer_info.key;
+    }
+    if let Some(approval_info) = approval_info {
+        publisher.approval_authority = *approval_info.key;
+    }
+    if is_open_for_offering_creation {
+        publisher.state |= PUBLISHER_IS_OPEN_FOR_OFFERING_CREATION;
+    } else {
+        publisher.state &= !PUBLISHER_IS_OPEN_FOR_OFFERING_CREATION;
+    }
+
+    PublisherAccount::pack(publisher, &mut publisher_pda_info.data.borrow_mut())?;
+
+    emit(&PublisherUpdatedEvent {
+        timestamp: sys_clock.unix_timestamp,
+        publisher_pda: *publisher_pda_info.key,
+        publisher,
+        authority: *authority_info.key,
+    })
+}
+
+pub(crate) fn load_publisher(
+    program_id: &Pubkey,
+    publisher_pda_info: &AccountInfo,
+) -> Result<PublisherAccount, ProgramError> {
+    if publisher_pda_info.owner.ne(program_id) {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    if is_closed_account(publisher_pda_info) {
+        return Err(ACCOUNT_IS_CLOSED_ERROR);
+    }
+
+    // unlike unpack_unchecked, unpack_from_slice also reads accounts that are not migrated yet
+    let publisher = PublisherAccount::unpack_from_slice(&publisher_pda_info.data.borrow())?;
+    if !publisher.is_initialized() {
+        return Err(INVALID_PUBLISHER_TYPE_ERROR);
+    }
+
+    Ok(publisher)
+}
+
+// a publisher that is going to be written back, a legacy one has to be migrated first
+// because its data is too short for the current layout
+pub(crate) fn load_current_publisher(
+    program_id: &Pubkey,
+    publisher_pda_info: &AccountInfo,
+) -> Result<PublisherAccount, ProgramError> {
+    let publisher = load_publisher(program_id, publisher_pda_info)?;
+    if publisher.version != ACCOUNT_VERSION {
+        return Err(ACCOUNT_IS_OUTDATED_ERROR);
+    }
+
+    Ok(publisher)
+}
+
+pub fn claim_publisher_authority(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> Result<(), ProgramError> {
+    let sys_clock = Clock::get()?;
+
+    let account_info_iter = &mut accounts.iter();
+
+    let claimer_info = next_account_info(account_info_iter)?;
+    let publisher_pda_info = next_account_info(account_info_iter)?;
+
+    if !claimer_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut publisher = load_current_publisher(program_id, publisher_pda_info)?;
+
+    // nothing to claim or cancel when no transfer of authority is pending
+    if publisher.transfer_authority.eq(&publisher.update_authority) {
+        return Err(UNAUTHORIZED_ERROR);
+    }
+
+    let previous_authority = publisher.update_authority;
+
+    if publisher.transfer_authority.eq(claimer_info.key) {
+        // the nominated authority accepts the transfer
+        publisher.update_authority = publisher.transfer_authority;
+    } else if publisher.update_authority.eq(claimer_info.key) {
+        // the current authority cancels the pending nomination
+        publisher.transfer_authority = publisher.update_authority;
+    } else {
+        return Err(UNAUTHORIZED_ERROR);
+    }
+
+    PublisherAccount::pack(publisher, &mut publisher_pda_info.data.borrow_mut())?;
+
+    emit(&PublisherAuthorityClaimedEvent {
+        timestamp: sys_clock.unix_timestamp,
+        publisher_pda: *publisher_pda_info.key,
+        publisher,
+        previous_authority,
+        claimer: *claimer_info.key,
+    })
+}
+
+// the custodial PDA is a plain system account holding the publisher revenue in SOL,
+// and the owner of the ATAs holding the revenue in SPL tokens
+pub(crate) fn verify_custodial(
+    program_id: &Pubkey,
+    publisher: &Pubkey,
+    custodial_pda_info: &AccountInfo,
+) -> Result<u8, ProgramError> {
+    let (custodial_pda, custodial_pda_bump) = find_custodial_address(program_id, publisher);
+    verify_pda(custodial_pda_info, &custodial_pda)?;
+
+    if custodial_pda_info.lamports() > 0 && custodial_pda_info.owner.ne(&system_program::id()) {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    Ok(custodial_pda_bump)
+}
+
+pub fn withdraw_publisher(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> Result<(), ProgramError> {
+    let sys_clock = Clock::get()?;
+    let sys_rent = Rent::get()?;
+
+    let account_info_iter = &mut accounts.iter();
+
+    let authority_info = next_account_info(account_info_iter)?;
+    let publisher_pda_info = next_account_info(account_info_iter)?;
+    let custodial_pda_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    if !authority_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let publisher = load_publisher(program_id, publisher_pda_info)?;
+    if publisher.update_authority.ne(authority_info.key) {
+        return Err(UNAUTHORIZED_ERROR);
+    }
+
+    let custodial_pda_bump =
+        verify_custodial(program_id, publisher_pda_info.key, custodial_pda_info)?;
+
+    let balance = custodial_pda_info.lamports();
+    if balance == 0 {
+        return Err(PUBLISHER_CUSTODIAL_BALANCE_IS_EMPTY_ERROR);
+    }
+
+    // zero withdraws the whole balance
+    let amount = if amount == 0 { balance } else { amount };
+    let remaining = balance
+        .checked_sub(amount)
+        .ok_or(TRANSFER_INSUFFICIENT_FUNDS_ERROR)?;
+
+    // a partially emptied custodial account still has to be rent exempt
+    if remaining > 0 && remaining < sys_rent.minimum_balance(0) {
+        return Err(TRANSFER_INSUFFICIENT_FUNDS_ERROR);
+    }
+
+    transfer_native(
+        system_program_info,
+        custodial_pda_info,
+        authority_info,
+        amount,
+        &[&[
+            CUSTODIAL_PDA_SEED,
+            publisher_pda_info.key.as_ref(),
+            &[custodial_pda_bump],
+        ]],
+    )?;
+
+    emit(&PublisherWithdrawalEvent {
+        timestamp: sys_clock.unix_timestamp,
+        publisher_pda: *publisher_pda_info.key,
+        custodial_pda: *custodial_pda_info.key,
+        authority: *authority_info.key,
+        amount,
+    })
+}
+
+pub fn withdraw_publisher_token(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> Result<(), ProgramError> {
+    let sys_clock = Clock::get()?;
+
+    let account_info_iter = &mut accounts.iter();
+
+    let authority_info = next_account_info(account_info_iter)?;
+    let publisher_pda_info = next_account_info(account_info_iter)?;
+    let custodial_pda_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let custodial_ata_info = next_account_info(account_info_iter)?;
+    let authority_ata_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+
+    if !authority_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let publisher = load_publisher(program_id, publisher_pda_info)?;
+    if publisher.update_authority.ne(authority_info.key) {
+        return Err(UNAUTHORIZED_ERROR);
+    }
+
+    let custodial_pda_bump =
+        verify_custodial(program_id, publisher_pda_info.key, custodial_pda_info)?;
+
+    verify_ata(
+        custodial_ata_info,
+        custodial_pda_info.key,
+        mint_info.key,
+        token_program_info.key,
+        INVALID_PUBLISHER_TOKEN_ATA_ERROR,
+    )?;
+
+    verify_ata(
+        authority_ata_info,
+        authority_info.key,
+        mint_info.key,
+        token_program_info.key,
+        INVALID_PUBLISHER_AUTHORITY_ATA_ERROR,
+    )?;
+
+    let balance = token_balance(custodial_ata_info, token_program_info)?;
+
+    // zero withdraws the whole balance
+    let amount = if amount == 0 { balance } else { amount };
+    if amount == 0 || amount > balance {
+        return Err(PUBLISHER_TOKEN_BALANCE_IS_INSUFFICIENT_ERROR);
+    }
+
+    transfer_tokens(
+        token_program_info,
+        custodial_ata_info,
+        mint_info,
+        authority_ata_info,
+        custodial_pda_info,
+        amount,
+        &[&[
+            CUSTODIAL_PDA_SEED,
+            publisher_pda_info.key.as_ref(),
+            &[custodial_pda_bump],
+        ]],
+    )?;
+
+    emit(&PublisherTokenWithdrawalEvent {
+        timestamp: sys_clock.unix_timestamp,
+        publisher_pda: *publisher_pda_info.key,
+        custodial_pda: *custodial_pda_info.key,
+        authority: *authority_info.key,
+        mint: *mint_info.key,
+        amount,
+    })
+}
+
+pub fn close_publisher_account(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> Result<(), ProgramError> {
+    let sys_clock = Clock::get()?;
+
+    let account_info_iter = &mut accounts.iter();
+
+    let authority_info = next_account_info(account_info_iter)?;
+    let publisher_pda_info = next_account_info(account_info_iter)?;
+    let custodial_pda_info = next_account_info(account_info_iter)?;
+    let receiver_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    if !authority_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let publisher = load_publisher(program_id, publisher_pda_info)?;
+    if publisher.update_authority.ne(authority_info.key) {
+        return Err(UNAUTHORIZED_ERROR);
+    }
+
+    // no new offering may point to the publisher once it is gone
+    if publisher.is_open_for_offering_creation() {
+        return Err(PUBLISHER_IS_OPEN_FOR_OFFERING_CREATION_ERROR);
+    }
+
+    // a publisher token left behind could not be closed, nor its revenue withdrawn
+    if publisher.num_of_publisher_tokens > 0 {
+        return Err(PUBLISHER_HAS_PUBLISHER_TOKENS_ERROR);
+    }
+
+    // a new publisher at the same address would adopt the offerings left behind
+    if publisher.num_of_offerings > 0 {
+        return Err(PUBLISHER_HAS_OFFERINGS_ERROR);
+    }
+
+    // a migrated legacy publisher can not tell whether the counters above are complete
+    if publisher.has_uncounted_accounts() {
+        return Err(PUBLISHER_HAS_UNCOUNTED_ACCOUNTS_ERROR);
+    }
+
+    let custodial_pda_bump =
+        verify_custodial(program_id, publisher_pda_info.key, custodial_pda_info)?;
+
+    // the SOL revenue goes along, SPL token revenue is withdrawn before closing its publisher token
+    let custodial_balance = custodial_pda_info.lamports();
+    if custodial_balance > 0 {
+        transfer_native(
+            system_program_info,
+            custodial_pda_info,
+            receiver_info,
+            custodial_balance,
+            &[&[
+                CUSTODIAL_PDA_SEED,
+                publisher_pda_info.key.as_ref(),
+                &[custodial_pda_bump],
+            ]],
+        )?;
+    }
+
+    close_pda_account(publisher_pda_info, receiver_info)?;
+
+    emit(&PublisherAccountClosedEvent {
+        timestamp: sys_clock.unix_timestamp,
+        publisher_pda: *publisher_pda_info.key,
+        authority: *authority_info.key,
+        receiver: *receiver_info.key,
+        custodial_balance,
+    })
+}
@@ -0,0 +1,329 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack},
+    pubkey::Pubkey,
+    rent::Rent,
+    system_program,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    constant::{
+        ACCOUNT_VERSION, INIT_PUBLISHER_TOKEN_MODE_PAYER_AND_AUTHORITY_ARE_THE_SAME,
+        INIT_PUBLISHER_TOKEN_MODE_TOKEN_PROGRAM_AND_SYSTEM_ARE_THE_SAME, PUBLISHER_TOKEN_PDA_SEED,
+        PUBLISHER_TOKEN_PDA_TYPE,
+    },
+    error::{
+        ACCOUNT_IS_CLOSED_ERROR, ACCOUNT_IS_OUTDATED_ERROR, INVALID_INSTRUCTION_ERROR,
+        INVALID_OFFER_CREATION_PRICE_ERROR, INVALID_PUBLISHER_TOKEN_ATA_ERROR,
+        PUBLISHER_TOKEN_CUSTODIAL_BALANCE_IS_NOT_EMPTY_ERROR, PUBLISHER_TOKEN_IS_INVALID,
+        PUBLISHER_TOKEN_IS_NOT_DISABLED_ERROR, UNAUTHORIZED_ERROR,
+    },
+    events::{
+        emit, PublisherTokenAccountClosedEvent, PublisherTokenInitiatedEvent,
+        PublisherTokenUpdatedEvent,
+    },
+    pda::find_publisher_token_address,
+    state::{
+        publisher::PublisherAccount,
+        publisher_token::{
+            PublisherTokenAccount, PUBLISHER_TOKEN_CONFIGURABLE_STATE,
+            PUBLISHER_TOKEN_IS_INITIALIZED,
+        },
+    },
+};
+
+use super::{
+    close_pda_account, create_pda_account, is_closed_account,
+    publisher::{load_current_publisher, load_publisher, verify_custodial},
+    token::{create_ata, token_balance, verify_ata, verify_mint},
+    verify_pda,
+};
+
+pub fn initiate_publisher_token(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    mode: u8,
+    state: u8,
+    offer_creation_price: u64,
+) -> Result<(), ProgramError> {
+    let sys_clock = Clock::get()?;
+
+    let account_info_iter = &mut accounts.iter();
+
+    let payer_info = next_account_info(account_info_iter)?;
+    let authority_info = if mode & INIT_PUBLISHER_TOKEN_MODE_PAYER_AND_AUTHORITY_ARE_THE_SAME == 0 {
+        next_account_info(account_info_iter)?
+    } else {
+        payer_info
+    };
+    let publisher_pda_info = next_account_info(account_info_iter)?;
+    let publisher_token_pda_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    if !payer_info.is_signer || !authority_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    // the publisher counts its publisher tokens, so it is written back
+    let mut publisher = load_current_publisher(program_id, publisher_pda_info)?;
+    if publisher.update_authority.ne(authority_info.key) {
+        return Err(UNAUTHORIZED_ERROR);
+    }
+
+    if state & !PUBLISHER_TOKEN_CONFIGURABLE_STATE != 0 {
+        return Err(INVALID_INSTRUCTION_ERROR);
+    }
+
+    // native SOL is whitelisted under the system program id
+    if mode & INIT_PUBLISHER_TOKEN_MODE_TOKEN_PROGRAM_AND_SYSTEM_ARE_THE_SAME == 0 {
+        let token_program_info = next_account_info(account_info_iter)?;
+        let custodial_pda_info = next_account_info(account_info_iter)?;
+        let custodial_ata_info = next_account_info(account_info_iter)?;
+        let ata_program_info = next_account_info(account_info_iter)?;
+
+        verify_mint(mint_info, token_program_info)?;
+        verify_custodial(program_id, publisher_pda_info.key, custodial_pda_info)?;
+        verify_ata(
+            custodial_ata_info,
+            custodial_pda_info.key,
+            mint_info.key,
+            token_program_info.key,
+            INVALID_PUBLISHER_TOKEN_ATA_ERROR,
+        )?;
+
+        // offer creation payments and withdrawals go through the custodial ATA
+        create_ata(
+            payer_info,
+            custodial_ata_info,
+            custodial_pda_info,
+            mint_info,
+            system_program_info,
+            token_program_info,
+            ata_program_info,
+        )?;
+    } else if mint_info.key.ne(&system_program::id()) {
+        return Err(PUBLISHER_TOKEN_IS_INVALID);
+    }
+
+    validate_offer_creation_price(mint_info.key, offer_creation_price)?;
+
+    let (publisher_token_pda, publisher_token_pda_bump) =
+        find_publisher_token_address(program_id, publisher_pda_info.key, mint_info.key);
+    verify_pda(publisher_token_pda_info, &publisher_token_pda)?;
+
+    create_pda_account(
+        program_id,
+        payer_info,
+        publisher_token_pda_info,
+        system_program_info,
+        PublisherTokenAccount::LEN,
+        &[
+            PUBLISHER_TOKEN_PDA_SEED,
+            publisher_pda_info.key.as_ref(),
+            mint_info.key.as_ref(),
+            &[publisher_token_pda_bump],
+        ],
+    )?;
+
+    let publisher_token = PublisherTokenAccount {
+        discriminator: PUBLISHER_TOKEN_PDA_TYPE,
+        version: ACCOUNT_VERSION,
+        state: state | PUBLISHER_TOKEN_IS_INITIALIZED,
+        publisher: *publisher_pda_info.key,
+        mint: *mint_info.key,
+        offer_creation_price,
+    };
+
+    PublisherTokenAccount::pack(
+        publisher_token,
+        &mut publisher_token_pda_info.data.borrow_mut(),
+    )?;
+
+    publisher.num_of_publisher_tokens = publisher
+        .num_of_publisher_tokens
+        .checked_add(1)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    PublisherAccount::pack(publisher, &mut publisher_pda_info.data.borrow_mut())?;
+
+    emit(&PublisherTokenInitiatedEvent {
+        timestamp: sys_clock.unix_timestamp,
+        publisher_token_pda: *publisher_token_pda_info.key,
+        publisher_token,
+        publisher_pda: *publisher_pda_info.key,
+        authority: *authority_info.key,
+    })
+}
+
+pub fn update_publisher_token(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    state: u8,
+    offer_creation_price: u64,
+) -> Result<(), ProgramError> {
+    let sys_clock = Clock::get()?;
+
+    let account_info_iter = &mut accounts.iter();
+
+    let authority_info = next_account_info(account_info_iter)?;
+    let publisher_pda_info = next_account_info(account_info_iter)?;
+    let publisher_token_pda_info = next_account_info(account_info_iter)?;
+
+    if !authority_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let publisher = load_publisher(program_id, publisher_pda_info)?;
+    if publisher.update_authority.ne(authority_info.key) {
+        return Err(UNAUTHORIZED_ERROR);
+    }
+
+    if state & !PUBLISHER_TOKEN_CONFIGURABLE_STATE != 0 {
+        return Err(INVALID_INSTRUCTION_ERROR);
+    }
+
+    let mut publisher_token =
+        load_publisher_token(program_id, publisher_pda_info.key, publisher_token_pda_info)?;
+
+    validate_offer_creation_price(&publisher_token.mint, offer_creation_price)?;
+
+    publisher_token.state = state | PUBLISHER_TOKEN_IS_INITIALIZED;
+    publisher_token.offer_creation_price = offer_creation_price;
+
+    PublisherTokenAccount::pack(
+        publisher_token,
+        &mut publisher_token_pda_info.data.borrow_mut(),
+    )?;
+
+    emit(&PublisherTokenUpdatedEvent {
+        timestamp: sys_clock.unix_timestamp,
+        publisher_token_pda: *publisher_token_pda_info.key,
+        publisher_token,
+        publisher_pda: *publisher_pda_info.key,
+        authority: *authority_info.key,
+    })
+}
+
+// a SOL price is paid into the custodial PDA, which holds no data: a price below
+// the rent exempt minimum would leave it underfunded whenever it starts out empty
+fn validate_offer_creation_price(
+    mint: &Pubkey,
+    offer_creation_price: u64,
+) -> Result<(), ProgramError> {
+    if mint.eq(&system_program::id())
+        && offer_creation_price > 0
+        && offer_creation_price < Rent::get()?.minimum_balance(0)
+    {
+        return Err(INVALID_OFFER_CREATION_PRICE_ERROR);
+    }
+
+    Ok(())
+}
+
+pub(crate) fn load_publisher_token(
+    program_id: &Pubkey,
+    publisher: &Pubkey,
+    publisher_token_pda_info: &AccountInfo,
+) -> Result<PublisherTokenAccount, ProgramError> {
+    if publisher_token_pda_info.owner.ne(program_id) {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    if is_closed_account(publisher_token_pda_info) {
+        return Err(ACCOUNT_IS_CLOSED_ERROR);
+    }
+
+    let publisher_token =
+        PublisherTokenAccount::unpack_from_slice(&publisher_token_pda_info.data.borrow())?;
+    if !publisher_token.is_initialized() || publisher_token.publisher.ne(publisher) {
+        return Err(PUBLISHER_TOKEN_IS_INVALID);
+    }
+
+    if publisher_token.version != ACCOUNT_VERSION {
+        return Err(ACCOUNT_IS_OUTDATED_ERROR);
+    }
+
+    let (publisher_token_pda, _) =
+        find_publisher_token_address(program_id, publisher, &publisher_token.mint);
+    verify_pda(publisher_token_pda_info, &publisher_token_pda)?;
+
+    Ok(publisher_token)
+}
+
+pub fn close_publisher_token_account(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> Result<(), ProgramError> {
+    let sys_clock = Clock::get()?;
+
+    let account_info_iter = &mut accounts.iter();
+
+    let authority_info = next_account_info(account_info_iter)?;
+    let publisher_pda_info = next_account_info(account_info_iter)?;
+    let publisher_token_pda_info = next_account_info(account_info_iter)?;
+    let receiver_info = next_account_info(account_info_iter)?;
+
+    if !authority_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut publisher = load_current_publisher(program_id, publisher_pda_info)?;
+    if publisher.update_authority.ne(authority_info.key) {
+        return Err(UNAUTHORIZED_ERROR);
+    }
+
+    let publisher_token =
+        load_publisher_token(program_id, publisher_pda_info.key, publisher_token_pda_info)?;
+
+    // disabling it first makes sure no offering is created with it in the meantime
+    if !publisher_token.is_disabled() {
+        return Err(PUBLISHER_TOKEN_IS_NOT_DISABLED_ERROR);
+    }
+
+    // the revenue in SPL tokens must be withdrawn first, nothing could be withdrawn from
+    // the custodial ATA once the publisher is closed
+    if publisher_token.mint.ne(&system_program::id()) {
+        let custodial_pda_info = next_account_info(account_info_iter)?;
+        let mint_info = next_account_info(account_info_iter)?;
+        let custodial_ata_info = next_account_info(account_info_iter)?;
+        let token_program_info = next_account_info(account_info_iter)?;
+
+        verify_custodial(program_id, publisher_pda_info.key, custodial_pda_info)?;
+
+        if publisher_token.mint.ne(mint_info.key) {
+            return Err(PUBLISHER_TOKEN_IS_INVALID);
+        }
+        verify_mint(mint_info, token_program_info)?;
+
+        verify_ata(
+            custodial_ata_info,
+            custodial_pda_info.key,
+            mint_info.key,
+            token_program_info.key,
+            INVALID_PUBLISHER_TOKEN_ATA_ERROR,
+        )?;
+
+        // the ATA is only created by the first offering paying out revenue in that token
+        if !custodial_ata_info.data_is_empty()
+            && token_balance(custodial_ata_info, token_program_info)? > 0
+        {
+            return Err(PUBLISHER_TOKEN_CUSTODIAL_BALANCE_IS_NOT_EMPTY_ERROR);
+        }
+    }
+
+    publisher.num_of_publisher_tokens = publisher.num_of_publisher_tokens.saturating_sub(1);
+    PublisherAccount::pack(publisher, &mut publisher_pda_info.data.borrow_mut())?;
+
+    close_pda_account(publisher_token_pda_info, receiver_info)?;
+
+    emit(&PublisherTokenAccountClosedEvent {
+        timestamp: sys_clock.unix_timestamp,
+        publisher_token_pda: *publisher_token_pda_info.key,
+        publisher_pda: *publisher_pda_info.key,
+        mint: publisher_token.mint,
+        receiver: *receiver_info.key,
+    })
+}
@@ -0,0 +1,610 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack},
+    pubkey::Pubkey,
+    system_program,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    constant::{
+        ACCOUNT_VERSION, INIT_PURCHASE_MODE_FUNDED_AND_SYSTEM_ARE_THE_SAME,
+        INIT_PURCHASE_MODE_PAYER_AND_SUBJECT_ARE_THE_SAME, OFFERING_PDA_SEED,
+        PURCHASE_MODE_FUNDED_AND_SYSTEM_ARE_THE_SAME, PURCHASE_MODE_PAYER_AND_SUBJECT_ARE_THE_SAME,
+        PURCHASE_PDA_SEED, PURCHASE_PDA_TYPE,
+    },
+    error::{
+        ACCOUNT_IS_CLOSED_ERROR, ACCOUNT_IS_OUTDATED_ERROR, INVALID_ATA_ERROR,
+        INVALID_DISTRIBUTION_DESTINATION_MINT_ATA_ERROR, INVALID_DISTRIBUTION_QUANTITY_CALC_ERROR,
+        INVALID_NUM_OF_VESTINGS_ERROR, INVALID_OFFERING_FUNDS_ATA_ERROR,
+        INVALID_PURCHASER_ATA_ERROR, INVALID_PURCHASE_OVER_MAX_QUANTITY_ERROR,
+        INVALID_PURCHASE_QUANTITY_ERROR, INVALID_PURCHASE_TOTAL_PRICE_ERROR,
+        INVALID_PURCHASE_TYPE_ERROR, INVALID_PURCHASE_UNDER_MIN_QUANTITY_ERROR,
+        OFFERING_DISTRIBUTED_QUANTITY_OVERFLOW_ERROR, OFFERING_FUNDING_AMOUNT_OVERFLOW_ERROR,
+        OFFERING_HAS_SOLD_OUT_ERROR, OFFERING_IS_NOT_APPROVED_BY_PUBLISHER_ERROR,
+        OFFERING_IS_NOT_READY_FOR_DISTRIUBTION_ERROR, OFFERING_IS_NOT_READY_FOR_PURCHASE_ERROR,
+        OFFERING_IS_ON_VESTING_ERROR, OFFERING_IS_STILL_PURCHASEABLE_ERROR,
+        OFFERING_MAX_FUNDING_EXCEEDED_ERROR, OFFERING_NEEDS_TO_BE_DISTRIBUTED_ERROR,
+        OFFERING_OFFER_IS_NOT_PROVIDED_ERROR, PURCHASE_DISTRIBUTION_IS_DISTRIBUTED_ERROR,
+        PURCHASE_DISTRIBUTION_QUANTITY_OVERFLOW_ERROR, PURCHASE_FUNDING_AMOUNT_OVERFLOW_ERROR,
+        PURCHASE_IS_NOT_DISTRIBUTED_ERROR, PURCHASE_OFFER_QUANTITY_OVERFLOW_ERROR,
+    },
+    events::{
+        emit, PurchaseAccountClosedEvent, PurchaseAddedEvent, PurchaseDistributionDistributedEvent,
+        PurchaseInitiatedEvent, PurchaseRefundedEvent,
+    },
+    pda::find_purchase_address,
+    state::{
+        offering::{MAX_NUM_OF_VESTINGS, OFFERING_HAS_SOLD_OUT},
+        purchase::{PurchaseAccount, PURCHASE_IS_INITIALIZED},
+        variable_len_pack::VariableLenPack,
+    },
+};
+
+use super::{
+    close_pda_account, create_pda_account, is_closed_account,
+    offering::{load_offering, load_offering_view, offering_pda_bump},
+    token::{token_balance, transfer_lamports, transfer_native, transfer_tokens, verify_ata},
+    verify_pda,
+};
+
+pub fn initiate_purchase(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    mode: u8,
+    amount: u64,
+) -> Result<(), ProgramError> {
+    let sys_clock = Clock::get()?;
+
+    let account_info_iter = &mut accounts.iter();
+
+    let payer_info = next_account_info(account_info_iter)?;
+    let purchaser_info = if mode & INIT_PURCHASE_MODE_PAYER_AND_SUBJECT_ARE_THE_SAME == 0 {
+        next_account_info(account_info_iter)?
+    } else {
+        payer_info
+    };
+    let offering_pda_info = next_account_info(account_info_iter)?;
+    let purchase_pda_info = next_account_info(account_info_iter)?;
+    let system_program_info = next_account_info(account_info_iter)?;
+
+    if !payer_info.is_signer || !purchaser_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let offering = load_offering_view(program_id, offering_pda_info)?;
+
+    if !offering.is_approved() {
+        return Err(OFFERING_IS_NOT_APPROVED_BY_PUBLISHER_ERROR);
+    }
+
+    if !offering.is_offer_provided() {
+        return Err(OFFERING_OFFER_IS_NOT_PROVIDED_ERROR);
+    }
+
+    if offering.has_sold_out() {
+        return Err(OFFERING_HAS_SOLD_OUT_ERROR);
+    }
+
+    if !offering.is_purchasable(sys_clock.unix_timestamp) {
+        return Err(OFFERING_IS_NOT_READY_FOR_PURCHASE_ERROR);
+    }
+
+    if amount == 0 {
+        return Err(INVALID_PURCHASE_QUANTITY_ERROR);
+    }
+
+    if amount > offering.fundable_amount() {
+        return Err(INVALID_PURCHASE_OVER_MAX_QUANTITY_ERROR);
+    }
+
+    let funded_mint = offering.funded_mint();
+    // the transfer below borrows the offering account again
+    drop(offering);
+
+    let (purchase_pda, purchase_pda_bump) =
+        find_purchase_address(program_id, offering_pda_info.key, purchaser_info.key);
+    verify_pda(purchase_pda_info, &purchase_pda)?;
+
+    let is_new_purchase = purchase_pda_info.data_len() == 0;
+
+    let mut purchase = if is_new_purchase {
+        PurchaseAccount {
+            discriminator: PURCHASE_PDA_TYPE,
+            version: ACCOUNT_VERSION,
+            state: PURCHASE_IS_INITIALIZED,
+            offering: *offering_pda_info.key,
+            purchaser: *purchaser_info.key,
+            ..PurchaseAccount::default()
+        }
+    } else {
+        // a purchase that already received its tokens is settled, it is not topped up again
+        let purchase = load_purchase(program_id, purchase_pda_info)?;
+        if purchase.distributed_quantity > 0 || purchase.distributed_vestings != 0 {
+            return Err(PURCHASE_DISTRIBUTION_IS_DISTRIBUTED_ERROR);
+        }
+        purchase
+    };
+
+    // the purchase is credited with what actually arrived, which is less than the
+    // amount sent when a Token-2022 transfer fee is charged on the funded mint
+    let received_amount = if mode & INIT_PURCHASE_MODE_FUNDED_AND_SYSTEM_ARE_THE_SAME == 0 {
+        let funded_mint_info = next_account_info(account_info_iter)?;
+        let purchaser_funds_ata_info = next_account_info(account_info_iter)?;
+        let offering_funds_ata_info = next_account_info(account_info_iter)?;
+        let funded_token_program_info = next_account_info(account_info_iter)?;
+
+        if funded_mint.ne(funded_mint_info.key) {
+            return Err(INVALID_OFFERING_FUNDS_ATA_ERROR);
+        }
+
+        verify_ata(
+            offering_funds_ata_info,
+            offering_pda_info.key,
+            funded_mint_info.key,
+            funded_token_program_info.key,
+            INVALID_OFFERING_FUNDS_ATA_ERROR,
+        )?;
+
+        let balance_before = token_balance(offering_funds_ata_info, funded_token_program_info)?;
+
+        transfer_tokens(
+            funded_token_program_info,
+            purchaser_funds_ata_info,
+            funded_mint_info,
+            offering_funds_ata_info,
+            purchaser_info,
+            amount,
+            &[],
+        )?;
+
+        token_balance(offering_funds_ata_info, funded_token_program_info)?
+            .checked_sub(balance_before)
+            .ok_or(OFFERING_FUNDING_AMOUNT_OVERFLOW_ERROR)?
+    } else {
+        if funded_mint.ne(&system_program::id()) {
+            return Err(INVALID_OFFERING_FUNDS_ATA_ERROR);
+        }
+
+        transfer_native(
+            system_program_info,
+            purchaser_info,
+            offering_pda_info,
+            amount,
+            &[],
+        )?;
+
+        amount
+    };
+
+    if received_amount == 0 {
+        return Err(INVALID_PURCHASE_QUANTITY_ERROR);
+    }
+
+    let offering = load_offering_view(program_id, offering_pda_info)?;
+
+    let purchase_funded_amount = purchase
+        .funded_amount
+        .checked_add(received_amount)
+        .ok_or(PURCHASE_FUNDING_AMOUNT_OVERFLOW_ERROR)?;
+
+    if purchase_funded_amount > offering.max_funding() {
+        return Err(OFFERING_MAX_FUNDING_EXCEEDED_ERROR);
+    }
+
+    // the last purchase is allowed to be under the minimum when that is all what is left
+    if purchase_funded_amount < offering.min_funding()
+        && received_amount != offering.fundable_amount()
+    {
+        return Err(INVALID_PURCHASE_UNDER_MIN_QUANTITY_ERROR);
+    }
+
+    let offering_funded_amount = offering
+        .funded_amount()
+        .checked_add(received_amount)
+        .ok_or(OFFERING_FUNDING_AMOUNT_OVERFLOW_ERROR)?;
+
+    // quantities are derived from the offering totals so the rounding of every
+    // purchase adds up to exactly the quantity sold by the whole offering
+    let purchased_quantity = offering
+        .offer_quantity_for(offering_funded_amount)
+        .and_then(|total| total.checked_sub(offering.offer_quantity_for(offering.funded_amount())?))
+        .ok_or(PURCHASE_OFFER_QUANTITY_OVERFLOW_ERROR)?;
+
+    if purchased_quantity == 0 {
+        return Err(INVALID_PURCHASE_TOTAL_PRICE_ERROR);
+    }
+
+    let hardcap = offering.hardcap();
+    let mut state = offering.state();
+    let mut num_of_purchasers = offering.num_of_purchasers();
+    let mut num_of_open_purchases = offering.num_of_open_purchases();
+    drop(offering);
+
+    if is_new_purchase {
+        create_pda_account(
+            program_id,
+            payer_info,
+            purchase_pda_info,
+            system_program_info,
+            PurchaseAccount::LEN,
+            &[
+                PURCHASE_PDA_SEED,
+                offering_pda_info.key.as_ref(),
+                purchaser_info.key.as_ref(),
+                &[purchase_pda_bump],
+            ],
+        )?;
+
+        num_of_purchasers = num_of_purchasers
+            .checked_add(1)
+            .ok_or(OFFERING_FUNDING_AMOUNT_OVERFLOW_ERROR)?;
+        num_of_open_purchases = num_of_open_purchases
+            .checked_add(1)
+            .ok_or(OFFERING_FUNDING_AMOUNT_OVERFLOW_ERROR)?;
+    }
+
+    purchase.funded_amount = purchase_funded_amount;
+    purchase.purchased_quantity = purchase
+        .purchased_quantity
+        .checked_add(purchased_quantity)
+        .ok_or(PURCHASE_OFFER_QUANTITY_OVERFLOW_ERROR)?;
+
+    if offering_funded_amount == hardcap {
+        state |= OFFERING_HAS_SOLD_OUT;
+    }
+
+    PurchaseAccount::pack(purchase, &mut purchase_pda_info.data.borrow_mut())?;
+
+    let mut offering = load_offering_view(program_id, offering_pda_info)?;
+    offering.set_funded_amount(offering_funded_amount);
+    offering.set_num_of_purchasers(num_of_purchasers);
+    offering.set_num_of_open_purchases(num_of_open_purchases);
+    offering.set_state(state);
+    drop(offering);
+
+    if is_new_purchase {
+        emit(&PurchaseInitiatedEvent {
+            timestamp: sys_clock.unix_timestamp,
+            purchase_pda: *purchase_pda_info.key,
+            purchase,
+            offering_pda: *offering_pda_info.key,
+            purchaser: *purchaser_info.key,
+            amount: received_amount,
+        })
+    } else {
+        emit(&PurchaseAddedEvent {
+            timestamp: sys_clock.unix_timestamp,
+            purchase_pda: *purchase_pda_info.key,
+            purchase,
+            offering_pda: *offering_pda_info.key,
+            purchaser: *purchaser_info.key,
+            amount: received_amount,
+        })
+    }
+}
+
+pub fn refund_purchase(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    mode: u8,
+) -> Result<(), ProgramError> {
+    let sys_clock = Clock::get()?;
+
+    let account_info_iter = &mut accounts.iter();
+
+    let payer_info = next_account_info(account_info_iter)?;
+    let purchaser_info = if mode & PURCHASE_MODE_PAYER_AND_SUBJECT_ARE_THE_SAME == 0 {
+        next_account_info(account_info_iter)?
+    } else {
+        payer_info
+    };
+    let offering_pda_info = next_account_info(account_info_iter)?;
+    let purchase_pda_info = next_account_info(account_info_iter)?;
+
+    if !payer_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut offering = load_offering(program_id, offering_pda_info)?;
+
+    if sys_clock.unix_timestamp <= offering.close_time {
+        return Err(OFFERING_IS_STILL_PURCHASEABLE_ERROR);
+    }
+
+    if offering.funded_amount >= offering.softcap {
+        return Err(OFFERING_NEEDS_TO_BE_DISTRIBUTED_ERROR);
+    }
+
+    let (purchase_pda, _) =
+        find_purchase_address(program_id, offering_pda_info.key, purchaser_info.key);
+    verify_pda(purchase_pda_info, &purchase_pda)?;
+
+    let purchase = load_purchase(program_id, purchase_pda_info)?;
+
+    let offering_pda_bump = offering_pda_bump(program_id, &offering, offering_pda_info)?;
+    let offering_pda_seeds: &[&[u8]] = &[
+        OFFERING_PDA_SEED,
+        offering.publisher.as_ref(),
+        offering.creator.as_ref(),
+        offering.offering_seed.as_ref(),
+        &[offering_pda_bump],
+    ];
+
+    if mode & PURCHASE_MODE_FUNDED_AND_SYSTEM_ARE_THE_SAME == 0 {
+        let funded_mint_info = next_account_info(account_info_iter)?;
+        let offering_funds_ata_info = next_account_info(account_info_iter)?;
+        let purchaser_funds_ata_info = next_account_info(account_info_iter)?;
+        let funded_token_program_info = next_account_info(account_info_iter)?;
+
+        if offering.funded_mint.ne(funded_mint_info.key) {
+            return Err(INVALID_OFFERING_FUNDS_ATA_ERROR);
+        }
+
+        verify_ata(
+            offering_funds_ata_info,
+            offering_pda_info.key,
+            funded_mint_info.key,
+            funded_token_program_info.key,
+            INVALID_OFFERING_FUNDS_ATA_ERROR,
+        )?;
+
+        verify_ata(
+            purchaser_funds_ata_info,
+            purchaser_info.key,
+            funded_mint_info.key,
+            funded_token_program_info.key,
+            INVALID_PURCHASER_ATA_ERROR,
+        )?;
+
+        transfer_tokens(
+            funded_token_program_info,
+            offering_funds_ata_info,
+            funded_mint_info,
+            purchaser_funds_ata_info,
+            offering_pda_info,
+            purchase.funded_amount,
+            &[offering_pda_seeds],
+        )?;
+    } else {
+        if offering.funded_mint.ne(&system_program::id()) {
+            return Err(INVALID_OFFERING_FUNDS_ATA_ERROR);
+        }
+
+        transfer_lamports(offering_pda_info, purchaser_info, purchase.funded_amount)?;
+    }
+
+    offering.num_of_open_purchases = offering.num_of_open_purchases.saturating_sub(1);
+    offering.pack(&mut offering_pda_info.data.borrow_mut())?;
+
+    close_pda_account(purchase_pda_info, purchaser_info)?;
+
+    emit(&PurchaseRefundedEvent {
+        timestamp: sys_clock.unix_timestamp,
+        purchase_pda: *purchase_pda_info.key,
+        offering_pda: *offering_pda_info.key,
+        purchaser: *purchaser_info.key,
+        funded_amount: purchase.funded_amount,
+    })
+}
+
+pub fn close_purchase_account(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> Result<(), ProgramError> {
+    let sys_clock = Clock::get()?;
+
+    let account_info_iter = &mut accounts.iter();
+
+    let purchaser_info = next_account_info(account_info_iter)?;
+    let offering_pda_info = next_account_info(account_info_iter)?;
+    let purchase_pda_info = next_account_info(account_info_iter)?;
+    let receiver_info = next_account_info(account_info_iter)?;
+
+    if !purchaser_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let (purchase_pda, _) =
+        find_purchase_address(program_id, offering_pda_info.key, purchaser_info.key);
+    verify_pda(purchase_pda_info, &purchase_pda)?;
+
+    let purchase = load_purchase(program_id, purchase_pda_info)?;
+
+    // refunded purchases are closed right away, the others once every vesting is claimed
+    if !purchase.is_all_vesting_distributed() {
+        return Err(PURCHASE_IS_NOT_DISTRIBUTED_ERROR);
+    }
+
+    // the offering is only closed once every purchase is
+    let mut offering = load_offering(program_id, offering_pda_info)?;
+    offering.num_of_open_purchases = offering.num_of_open_purchases.saturating_sub(1);
+    offering.pack(&mut offering_pda_info.data.borrow_mut())?;
+
+    close_pda_account(purchase_pda_info, receiver_info)?;
+
+    emit(&PurchaseAccountClosedEvent {
+        timestamp: sys_clock.unix_timestamp,
+        purchase_pda: *purchase_pda_info.key,
+        offering_pda: *offering_pda_info.key,
+        purchaser: *purchaser_info.key,
+        receiver: *receiver_info.key,
+    })
+}
+
+pub(crate) fn load_purchase(
+    program_id: &Pubkey,
+    purchase_pda_info: &AccountInfo,
+) -> Result<PurchaseAccount, ProgramError> {
+    if purchase_pda_info.owner.ne(program_id) {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    if is_closed_account(purchase_pda_info) {
+        return Err(ACCOUNT_IS_CLOSED_ERROR);
+    }
+
+    let purchase = PurchaseAccount::unpack_from_slice(&purchase_pda_info.data.borrow())?;
+    if !purchase.is_initialized() {
+        return Err(INVALID_PURCHASE_TYPE_ERROR);
+    }
+
+    if purchase.version != ACCOUNT_VERSION {
+        return Err(ACCOUNT_IS_OUTDATED_ERROR);
+    }
+
+    Ok(purchase)
+}
+
+pub fn distribute(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    mode: u8,
+) -> Result<(), ProgramError> {
+    let sys_clock = Clock::get()?;
+
+    let account_info_iter = &mut accounts.iter();
+
+    let payer_info = next_account_info(account_info_iter)?;
+    let purchaser_info = if mode & PURCHASE_MODE_PAYER_AND_SUBJECT_ARE_THE_SAME == 0 {
+        next_account_info(account_info_iter)?
+    } else {
+        payer_info
+    };
+    let offering_pda_info = next_account_info(account_info_iter)?;
+    let purchase_pda_info = next_account_info(account_info_iter)?;
+    let offered_mint_info = next_account_info(account_info_iter)?;
+    let offering_offered_ata_info = next_account_info(account_info_iter)?;
+    let purchaser_offered_ata_info = next_account_info(account_info_iter)?;
+    let offered_token_program_info = next_account_info(account_info_iter)?;
+
+    if !payer_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+
+    let mut offering = load_offering(program_id, offering_pda_info)?;
+
+    if sys_clock.unix_timestamp <= offering.close_time || offering.funded_amount < offering.softcap
+    {
+        return Err(OFFERING_IS_NOT_READY_FOR_DISTRIUBTION_ERROR);
+    }
+
+    if offering.vestings.len() > MAX_NUM_OF_VESTINGS {
+        return Err(INVALID_NUM_OF_VESTINGS_ERROR);
+    }
+
+    let (purchase_pda, _) =
+        find_purchase_address(program_id, offering_pda_info.key, purchaser_info.key);
+    verify_pda(purchase_pda_info, &purchase_pda)?;
+
+    let mut purchase = load_purchase(program_id, purchase_pda_info)?;
+
+    if offering.offered_mint.ne(offered_mint_info.key) {
+        return Err(INVALID_ATA_ERROR);
+    }
+
+    verify_ata(
+        offering_offered_ata_info,
+        offering_pda_info.key,
+        offered_mint_info.key,
+        offered_token_program_info.key,
+        INVALID_ATA_ERROR,
+    )?;
+
+    verify_ata(
+        purchaser_offered_ata_info,
+        purchaser_info.key,
+        offered_mint_info.key,
+        offered_token_program_info.key,
+        INVALID_DISTRIBUTION_DESTINATION_MINT_ATA_ERROR,
+    )?;
+
+    let num_of_vestings = offering.vestings.len();
+    let mut num_of_distributed_vestings = 0;
+    let mut distribution_quantity: u64 = 0;
+
+    for (index, vesting) in offering.vestings.iter().enumerate() {
+        if sys_clock.unix_timestamp <= vesting.release_time {
+            break;
+        }
+
+        if purchase.is_vesting_distributed(index) {
+            continue;
+        }
+
+        // the last vesting takes whatever the rounding of the previous ones left behind
+        let quantity = if index == num_of_vestings - 1 {
+            purchase
+                .purchased_quantity
+                .checked_sub(purchase.distributed_quantity)
+                .ok_or(INVALID_DISTRIBUTION_QUANTITY_CALC_ERROR)?
+        } else {
+            u64::try_from(
+                (purchase.purchased_quantity as u128) * (vesting.percentage as u128) / 100,
+            )
+            .map_err(|_| INVALID_DISTRIBUTION_QUANTITY_CALC_ERROR)?
+        };
+
+        purchase.distributed_vestings |= 1 << index;
+        num_of_distributed_vestings += 1;
+        purchase.distributed_quantity = purchase
+            .distributed_quantity
+            .checked_add(quantity)
+            .ok_or(PURCHASE_DISTRIBUTION_QUANTITY_OVERFLOW_ERROR)?;
+        offering.distributed_quantity = offering
+            .distributed_quantity
+            .checked_add(quantity)
+            .ok_or(OFFERING_DISTRIBUTED_QUANTITY_OVERFLOW_ERROR)?;
+        distribution_quantity = distribution_quantity
+            .checked_add(quantity)
+            .ok_or(PURCHASE_DISTRIBUTION_QUANTITY_OVERFLOW_ERROR)?;
+
+        if purchase.distributed_quantity > purchase.purchased_quantity {
+            return Err(PURCHASE_DISTRIBUTION_QUANTITY_OVERFLOW_ERROR);
+        }
+
+        emit(&PurchaseDistributionDistributedEvent {
+            timestamp: sys_clock.unix_timestamp,
+            purchase_pda: *purchase_pda_info.key,
+            offering_pda: *offering_pda_info.key,
+            purchaser: *purchaser_info.key,
+            vesting_index: index as u8,
+            quantity,
+        })?;
+    }
+
+    if num_of_distributed_vestings == 0 {
+        if (0..num_of_vestings).all(|index| purchase.is_vesting_distributed(index)) {
+            return Err(PURCHASE_DISTRIBUTION_IS_DISTRIBUTED_ERROR);
+        }
+        return Err(OFFERING_IS_ON_VESTING_ERROR);
+    }
+
+    PurchaseAccount::pack(purchase, &mut purchase_pda_info.data.borrow_mut())?;
+    offering.pack(&mut offering_pda_info.data.borrow_mut())?;
+
+    if distribution_quantity == 0 {
+        return Ok(());
+    }
+
+    let offering_pda_bump = offering_pda_bump(program_id, &offering, offering_pda_info)?;
+    let offering_pda_seeds: &[&[u8]] = &[
+        OFFERING_PDA_SEED,
+        offering.publisher.as_ref(),
+        offering.creator.as_ref(),
+        offering.offering_seed.as_ref(),
+        &[offering_pda_bump],
+    ];
+
+    transfer_tokens(
+        offered_token_program_info,
+        offering_offered_ata_info,
+        offered_mint_info,
+        purchaser_offered_ata_info,
+        offering_pda_info,
+        distribution_quantity,
+        &[offering_pda_seeds],
+    )
+}
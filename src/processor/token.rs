@@ -0,0 +1,181 @@
+use solana_program::{
+    account_info::AccountInfo,
+    log,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_instruction,
+};
+use spl_associated_token_account::instruction::create_associated_token_account_idempotent;
+use spl_associated_token_account_client::address::get_associated_token_address_with_program_id;
+use spl_token_2022::{
+    check_spl_token_program_account,
+    extension::StateWithExtensions,
+    state::{Account, Mint},
+};
+
+use crate::error::TRANSFER_INSUFFICIENT_FUNDS_ERROR;
+
+pub(crate) fn verify_ata(
+    ata_info: &AccountInfo,
+    wallet: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+    error: ProgramError,
+) -> Result<(), ProgramError> {
+    let ata = get_associated_token_address_with_program_id(wallet, mint, token_program);
+    if ata.ne(ata_info.key) {
+        log::sol_log_data(&[ata.as_ref()]);
+        return Err(error);
+    }
+    Ok(())
+}
+
+pub(crate) fn transfer_tokens<'a>(
+    token_program_info: &AccountInfo<'a>,
+    source_info: &AccountInfo<'a>,
+    mint_info: &AccountInfo<'a>,
+    destination_info: &AccountInfo<'a>,
+    authority_info: &AccountInfo<'a>,
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<(), ProgramError> {
+    check_spl_token_program_account(token_program_info.key)?;
+
+    if mint_info.owner.ne(token_program_info.key) {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let decimals = {
+        let data = mint_info.data.borrow();
+        StateWithExtensions::<Mint>::unpack(&data)?.base.decimals
+    };
+
+    invoke_signed(
+        &spl_token_2022::instruction::transfer_checked(
+            token_program_info.key,
+            source_info.key,
+            mint_info.key,
+            destination_info.key,
+            authority_info.key,
+            &[],
+            amount,
+            decimals,
+        )?,
+        &[
+            source_info.clone(),
+            mint_info.clone(),
+            destination_info.clone(),
+            authority_info.clone(),
+            token_program_info.clone(),
+        ],
+        signer_seeds,
+    )
+}
+
+// accepts mints of both SPL Token and Token-2022
+pub(crate) fn verify_mint(
+    mint_info: &AccountInfo,
+    token_program_info: &AccountInfo,
+) -> Result<(), ProgramError> {
+    check_spl_token_program_account(token_program_info.key)?;
+
+    if mint_info.owner.ne(token_program_info.key) {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let data = mint_info.data.borrow();
+    StateWithExtensions::<Mint>::unpack(&data)?;
+
+    Ok(())
+}
+
+pub(crate) fn token_balance(
+    token_account_info: &AccountInfo,
+    token_program_info: &AccountInfo,
+) -> Result<u64, ProgramError> {
+    check_spl_token_program_account(token_program_info.key)?;
+
+    if token_account_info.owner.ne(token_program_info.key) {
+        return Err(ProgramError::IllegalOwner);
+    }
+
+    let data = token_account_info.data.borrow();
+    Ok(StateWithExtensions::<Account>::unpack(&data)?.base.amount)
+}
+
+// creates the ATA of the wallet unless it exists already, the payer funds its rent
+pub(crate) fn create_ata<'a>(
+    payer_info: &AccountInfo<'a>,
+    ata_info: &AccountInfo<'a>,
+    wallet_info: &AccountInfo<'a>,
+    mint_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+    token_program_info: &AccountInfo<'a>,
+    ata_program_info: &AccountInfo<'a>,
+) -> Result<(), ProgramError> {
+    if ata_program_info.key.ne(&spl_associated_token_account::id()) {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    invoke(
+        &create_associated_token_account_idempotent(
+            payer_info.key,
+            wallet_info.key,
+            mint_info.key,
+            token_program_info.key,
+        ),
+        &[
+            payer_info.clone(),
+            ata_info.clone(),
+            wallet_info.clone(),
+            mint_info.clone(),
+            system_program_info.clone(),
+            token_program_info.clone(),
+            ata_program_info.clone(),
+        ],
+    )
+}
+
+// moves SOL out of an account owned by the system program, e.g. a signer or a custodial PDA
+pub(crate) fn transfer_native<'a>(
+    system_program_info: &AccountInfo<'a>,
+    source_info: &AccountInfo<'a>,
+    destination_info: &AccountInfo<'a>,
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<(), ProgramError> {
+    let instruction = system_instruction::transfer(source_info.key, destination_info.key, amount);
+    let account_infos = [
+        source_info.clone(),
+        destination_info.clone(),
+        system_program_info.clone(),
+    ];
+
+    if signer_seeds.is_empty() {
+        invoke(&instruction, &account_infos)
+    } else {
+        invoke_signed(&instruction, &account_infos, signer_seeds)
+    }
+}
+
+// moves SOL out of an account owned by this program, e.g. an offering PDA
+pub(crate) fn transfer_lamports(
+    source_info: &AccountInfo,
+    destination_info: &AccountInfo,
+    amount: u64,
+) -> Result<(), ProgramError> {
+    let source_lamports = source_info
+        .lamports()
+        .checked_sub(amount)
+        .ok_or(TRANSFER_INSUFFICIENT_FUNDS_ERROR)?;
+    let destination_lamports = destination_info
+        .lamports()
+        .checked_add(amount)
+        .ok_or(TRANSFER_INSUFFICIENT_FUNDS_ERROR)?;
+
+    **source_info.try_borrow_mut_lamports()? = source_lamports;
+    **destination_info.try_borrow_mut_lamports()? = destination_lamports;
+
+    Ok(())
+}
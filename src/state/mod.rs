@@ -1,3 +1,17 @@
 pub mod offering;
+pub mod publisher;
 pub mod publisher_token;
-pub mod publisher;
\ No newline at end of file
+pub mod purchase;
+pub mod variable_len_pack;
+
+use crate::constant::LEGACY_ACCOUNT_VERSION;
+
+// accounts created before the version byte was introduced go straight from the
+// discriminator to the state, they are read as LEGACY_ACCOUNT_VERSION
+pub(crate) fn split_version(rest: &[u8], is_legacy: bool) -> (&[u8], &[u8]) {
+    if is_legacy {
+        (&[LEGACY_ACCOUNT_VERSION], rest)
+    } else {
+        rest.split_at(1)
+    }
+}
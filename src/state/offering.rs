@@ -1,6 +1,18 @@
-use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+use std::ops::{Deref, DerefMut};
 
-use crate::constant::OFFERING_PDA_TYPE;
+use solana_program::{
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Sealed},
+    pubkey::Pubkey,
+};
+
+use crate::{
+    constant::{ACCOUNT_VERSION, OFFERING_PDA_TYPE},
+    error::{
+        INVALID_NUM_OF_VESTINGS_ERROR, INVALID_OFFERING_LEN_ERROR, INVALID_OFFERING_TYPE_ERROR,
+    },
+    state::variable_len_pack::VariableLenPack,
+};
 
 pub const OFFERING_IS_INITIALIZED: u8 = 0b00000001;
 pub const OFFERING_HAS_SOLD_OUT: u8 = 0b00000100;
@@ -10,9 +22,15 @@ pub const OFFERING_OFFER_IS_PROVIDED: u8 = 0b00100000;
 pub const OFFERING_FUNDS_IS_WITHDRAWN: u8 = 0b01000000;
 pub const OFFERING_FUNDS_IS_DEPOSITED_TO_MARKET_POOL: u8 = 0b10000000;
 
+// bounded by the width of PurchaseAccount::distributed_vestings
+pub const MAX_NUM_OF_VESTINGS: usize = 64;
+pub const MAX_METADATA_CID_LEN: usize = 128;
+
 #[repr(C)]
+#[derive(Clone, Debug, Default, PartialEq)]
 pub struct OfferingAccount {
     pub discriminator: u8,
+    pub version: u8,
     pub state: u8,
     pub publisher: Pubkey,
     pub creator: Pubkey,
@@ -32,12 +50,16 @@ pub struct OfferingAccount {
     pub open_time: i64,
     pub close_time: i64,
     pub exit_time: i64,
+    // every purchaser the offering ever had, it never goes down
     pub num_of_purchasers: u64,
+    // purchase PDAs that are not refunded or closed yet
+    pub num_of_open_purchases: u64,
     pub metadata_cid: Vec<u8>,
     pub vestings: Vec<OfferingVesting>,
 }
 
 #[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub struct OfferingVesting {
     pub release_time: i64,
     /**
@@ -46,12 +68,16 @@ pub struct OfferingVesting {
     pub percentage: u8,
 }
 
-impl OfferingAccount {
-    pub fn is_initialized(&self) -> bool {
+impl Sealed for OfferingAccount {}
+
+impl IsInitialized for OfferingAccount {
+    fn is_initialized(&self) -> bool {
         self.discriminator == OFFERING_PDA_TYPE
             && (self.state & OFFERING_IS_INITIALIZED) == OFFERING_IS_INITIALIZED
     }
+}
 
+impl OfferingAccount {
     pub fn is_published(&self, time: i64) -> bool {
         (self.state & OFFERING_IS_INITIALIZED) == OFFERING_IS_INITIALIZED
             && time > self.publish_time
@@ -71,21 +97,71 @@ impl OfferingAccount {
 
     pub fn is_first_vesting_released(&self, time: i64) -> bool {
         (self.state & OFFERING_IS_INITIALIZED) == OFFERING_IS_INITIALIZED
-            && self.vestings.len() > 0
+            && !self.vestings.is_empty()
             && time > self.vestings[0].release_time
     }
 
+    pub fn is_approved(&self) -> bool {
+        (self.state & OFFERING_IS_APPROVED_BY_PUBLISHER) == OFFERING_IS_APPROVED_BY_PUBLISHER
+    }
+
+    pub fn is_rejected(&self) -> bool {
+        (self.state & OFFERING_IS_REJECTED_BY_PUBLISHER) == OFFERING_IS_REJECTED_BY_PUBLISHER
+    }
+
+    pub fn is_offer_provided(&self) -> bool {
+        (self.state & OFFERING_OFFER_IS_PROVIDED) == OFFERING_OFFER_IS_PROVIDED
+    }
+
+    pub fn is_deposited_to_market_pool(&self) -> bool {
+        (self.state & OFFERING_FUNDS_IS_DEPOSITED_TO_MARKET_POOL)
+            == OFFERING_FUNDS_IS_DEPOSITED_TO_MARKET_POOL
+    }
+
+    pub fn is_funds_withdrawn(&self) -> bool {
+        (self.state & OFFERING_FUNDS_IS_WITHDRAWN) == OFFERING_FUNDS_IS_WITHDRAWN
+    }
+
     pub fn has_sold_out(&self) -> bool {
         (self.state & OFFERING_HAS_SOLD_OUT) == OFFERING_HAS_SOLD_OUT
     }
 
     pub fn fundable_amount(&self) -> u64 {
-        self.hardcap - self.funded_amount
+        self.hardcap.saturating_sub(self.funded_amount)
+    }
+
+    // offered tokens escrowed on top of the offer, to pair with the market pool share of the funds
+    pub fn market_pool_reserve(&self) -> u64 {
+        ((self.offer_quantity as u128) * (self.market_pool_percentage as u128) / 100) as u64
+    }
+
+    // raised funds that go to the market pool instead of the promoter
+    pub fn market_pool_amount(&self) -> u64 {
+        ((self.funded_amount as u128) * (self.market_pool_percentage as u128) / 100) as u64
+    }
+
+    pub fn escrow_quantity(&self) -> Option<u64> {
+        self.offer_quantity.checked_add(self.market_pool_reserve())
     }
+
+    // offered tokens bought with the given funds, at the price of offer_quantity per hardcap
+    pub fn offer_quantity_for(&self, funded_amount: u64) -> Option<u64> {
+        calc_offer_quantity(funded_amount, self.offer_quantity, self.hardcap)
+    }
+}
+
+fn calc_offer_quantity(funded_amount: u64, offer_quantity: u64, hardcap: u64) -> Option<u64> {
+    if hardcap == 0 {
+        return None;
+    }
+    let quantity = (funded_amount as u128).checked_mul(offer_quantity as u128)? / (hardcap as u128);
+    u64::try_from(quantity).ok()
 }
 
+#[allow(clippy::identity_op)]
 const FIXED_OFFERING_ACCOUNT_DATA_LEN: usize = 0
     + 1 // discriminator
+    + 1 // version
     + 1 // state
     + 32 // publisher
     + 32 // creator
@@ -106,170 +182,654 @@ const FIXED_OFFERING_ACCOUNT_DATA_LEN: usize = 0
     + 8 // close_time
     + 8 // exit_time
     + 8 // num_of_purchasers
+    + 8 // num_of_open_purchases
     + 8 // metadata_cid_len
     + 1 // num_of_vestings
     ;
 
-pub fn calc_offering_account_len(offering: &OfferingAccount) -> usize {
-    let len = FIXED_OFFERING_ACCOUNT_DATA_LEN
-        + offering.metadata_cid.len() // metadata_cid
-        + (offering.vestings.len() * (8 + 1)) // vestings
-        ;
+#[allow(clippy::identity_op)]
+const OFFERING_VESTING_DATA_LEN: usize = 0
+    + 8 // release_time
+    + 1 // percentage
+    ;
 
-    return len;
-}
+// byte offsets of the fixed fields, see FIXED_OFFERING_ACCOUNT_DATA_LEN
+const VERSION_OFFSET: usize = 1;
+const STATE_OFFSET: usize = 2;
+const PUBLISHER_OFFSET: usize = 3;
+const CREATOR_OFFSET: usize = 35;
+const OFFERING_SEED_OFFSET: usize = 67;
+const PROMOTER_OFFSET: usize = 99;
+const FUNDED_MINT_OFFSET: usize = 131;
+const OFFERED_MINT_OFFSET: usize = 163;
+const SOFTCAP_OFFSET: usize = 195;
+const HARDCAP_OFFSET: usize = 203;
+const FUNDED_AMOUNT_OFFSET: usize = 211;
+const MIN_FUNDING_OFFSET: usize = 219;
+const MAX_FUNDING_OFFSET: usize = 227;
+const OFFER_QUANTITY_OFFSET: usize = 235;
+const DISTRIBUTED_QUANTITY_OFFSET: usize = 243;
+const MARKET_POOL_PERCENTAGE_OFFSET: usize = 251;
+const PUBLISH_TIME_OFFSET: usize = 252;
+const OPEN_TIME_OFFSET: usize = 260;
+const CLOSE_TIME_OFFSET: usize = 268;
+const EXIT_TIME_OFFSET: usize = 276;
+const NUM_OF_PURCHASERS_OFFSET: usize = 284;
+const NUM_OF_OPEN_PURCHASES_OFFSET: usize = 292;
+const METADATA_CID_LEN_OFFSET: usize = 300;
+const NUM_OF_VESTINGS_OFFSET: usize = 308;
+
+const _: () = assert!(NUM_OF_VESTINGS_OFFSET + 1 == FIXED_OFFERING_ACCOUNT_DATA_LEN);
 
-pub fn check_offering_account_data(src: &[u8]) -> bool {
+// validates every length of the account data and returns the metadata_cid
+// length and the number of vestings, so reading the fields can not go out of bounds
+fn check_offering_account_layout(src: &[u8]) -> Result<(usize, usize), ProgramError> {
     if src.len() < FIXED_OFFERING_ACCOUNT_DATA_LEN {
-        return false;
+        return Err(INVALID_OFFERING_LEN_ERROR);
     }
 
     if src[0] != OFFERING_PDA_TYPE {
-        return false;
+        return Err(INVALID_OFFERING_TYPE_ERROR);
+    }
+
+    let metadata_cid_len = u64::from_le_bytes(
+        src[METADATA_CID_LEN_OFFSET..METADATA_CID_LEN_OFFSET + 8]
+            .try_into()
+            .unwrap(),
+    );
+    if metadata_cid_len > MAX_METADATA_CID_LEN as u64 {
+        return Err(INVALID_OFFERING_LEN_ERROR);
+    }
+    let metadata_cid_len = metadata_cid_len as usize;
+
+    let num_of_vestings = src[NUM_OF_VESTINGS_OFFSET] as usize;
+    if num_of_vestings > MAX_NUM_OF_VESTINGS {
+        return Err(INVALID_NUM_OF_VESTINGS_ERROR);
     }
 
-    if (src[1] & OFFERING_IS_INITIALIZED) != OFFERING_IS_INITIALIZED {
-        return false;
+    // the variable part has to fill the rest of the account exactly
+    if src.len()
+        != FIXED_OFFERING_ACCOUNT_DATA_LEN
+            + metadata_cid_len
+            + num_of_vestings * OFFERING_VESTING_DATA_LEN
+    {
+        return Err(INVALID_OFFERING_LEN_ERROR);
     }
 
-    return true;
+    Ok((metadata_cid_len, num_of_vestings))
 }
 
-pub fn unpack_offering_account(src: &[u8]) -> Result<OfferingAccount, ProgramError> {
-    let (discriminator, rest) = src.split_at(1);
-    let (state, rest) = rest.split_at(1);
-    let (publisher, rest) = rest.split_at(32);
-    let (creator, rest) = rest.split_at(32);
-    let (offering_seed, rest) = rest.split_at(32);
-    let (promoter, rest) = rest.split_at(32);
-    let (funded_mint, rest) = rest.split_at(32);
-    let (offered_mint, rest) = rest.split_at(32);
-    let (softcap, rest) = rest.split_at(8);
-    let (hardcap, rest) = rest.split_at(8);
-    let (funded_amount, rest) = rest.split_at(8);
-    let (min_funding, rest) = rest.split_at(8);
-    let (max_funding, rest) = rest.split_at(8);
-    let (offer_quantity, rest) = rest.split_at(8);
-    let (distributed_quantity, rest) = rest.split_at(8);
-    let (market_pool_percentage, rest) = rest.split_at(1);
-    let (publish_time, rest) = rest.split_at(8);
-    let (open_time, rest) = rest.split_at(8);
-    let (close_time, rest) = rest.split_at(8);
-    let (exit_time, rest) = rest.split_at(8);
-    let (num_of_purchasers, rest) = rest.split_at(8);
-    let (metatadata_cid_len_data, rest) = rest.split_at(8);
-    let (num_of_vestings, rest) = rest.split_at(1);
-
-    let metadata_cid_len = u64::from_le_bytes(metatadata_cid_len_data.try_into().unwrap()) as usize;
-    let (metadata_cid, rest) = rest.split_at(metadata_cid_len);
-
-    let mut vesting_src = rest;
-
-    let vestings = (0..num_of_vestings[0])
-        .map(|_| {
-            let (release_time, rest) = vesting_src.split_at(8);
-            let (percentage, rest) = rest.split_at(1);
-            vesting_src = rest;
-            OfferingVesting {
-                release_time: i64::from_le_bytes(release_time.try_into().unwrap()),
-                percentage: percentage[0],
-            }
+impl VariableLenPack for OfferingAccount {
+    fn get_packed_len(&self) -> usize {
+        FIXED_OFFERING_ACCOUNT_DATA_LEN
+            + self.metadata_cid.len() // metadata_cid
+            + (self.vestings.len() * OFFERING_VESTING_DATA_LEN) // vestings
+    }
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let (metadata_cid_len, _) = check_offering_account_layout(src)?;
+
+        let (discriminator, rest) = src.split_at(1);
+        let (version, rest) = rest.split_at(1);
+        let (state, rest) = rest.split_at(1);
+        let (publisher, rest) = rest.split_at(32);
+        let (creator, rest) = rest.split_at(32);
+        let (offering_seed, rest) = rest.split_at(32);
+        let (promoter, rest) = rest.split_at(32);
+        let (funded_mint, rest) = rest.split_at(32);
+        let (offered_mint, rest) = rest.split_at(32);
+        let (softcap, rest) = rest.split_at(8);
+        let (hardcap, rest) = rest.split_at(8);
+        let (funded_amount, rest) = rest.split_at(8);
+        let (min_funding, rest) = rest.split_at(8);
+        let (max_funding, rest) = rest.split_at(8);
+        let (offer_quantity, rest) = rest.split_at(8);
+        let (distributed_quantity, rest) = rest.split_at(8);
+        let (market_pool_percentage, rest) = rest.split_at(1);
+        let (publish_time, rest) = rest.split_at(8);
+        let (open_time, rest) = rest.split_at(8);
+        let (close_time, rest) = rest.split_at(8);
+        let (exit_time, rest) = rest.split_at(8);
+        let (num_of_purchasers, rest) = rest.split_at(8);
+        let (num_of_open_purchases, rest) = rest.split_at(8);
+        let (_metadata_cid_len, rest) = rest.split_at(8);
+        let (_num_of_vestings, rest) = rest.split_at(1);
+
+        let (metadata_cid, rest) = rest.split_at(metadata_cid_len);
+
+        let vestings = rest
+            .chunks_exact(OFFERING_VESTING_DATA_LEN)
+            .map(|vesting_src| {
+                let (release_time, percentage) = vesting_src.split_at(8);
+                OfferingVesting {
+                    release_time: i64::from_le_bytes(release_time.try_into().unwrap()),
+                    percentage: percentage[0],
+                }
+            })
+            .collect();
+
+        Ok(OfferingAccount {
+            discriminator: discriminator[0],
+            version: version[0],
+            state: state[0],
+            publisher: Pubkey::new_from_array((*publisher).try_into().unwrap()),
+            creator: Pubkey::new_from_array((*creator).try_into().unwrap()),
+            offering_seed: Pubkey::new_from_array((*offering_seed).try_into().unwrap()),
+            promoter: Pubkey::new_from_array((*promoter).try_into().unwrap()),
+            funded_mint: Pubkey::new_from_array((*funded_mint).try_into().unwrap()),
+            offered_mint: Pubkey::new_from_array((*offered_mint).try_into().unwrap()),
+            softcap: u64::from_le_bytes(softcap.try_into().unwrap()),
+            hardcap: u64::from_le_bytes(hardcap.try_into().unwrap()),
+            funded_amount: u64::from_le_bytes(funded_amount.try_into().unwrap()),
+            min_funding: u64::from_le_bytes(min_funding.try_into().unwrap()),
+            max_funding: u64::from_le_bytes(max_funding.try_into().unwrap()),
+            offer_quantity: u64::from_le_bytes(offer_quantity.try_into().unwrap()),
+            distributed_quantity: u64::from_le_bytes(distributed_quantity.try_into().unwrap()),
+            market_pool_percentage: market_pool_percentage[0],
+            publish_time: i64::from_le_bytes(publish_time.try_into().unwrap()),
+            open_time: i64::from_le_bytes(open_time.try_into().unwrap()),
+            close_time: i64::from_le_bytes(close_time.try_into().unwrap()),
+            exit_time: i64::from_le_bytes(exit_time.try_into().unwrap()),
+            num_of_purchasers: u64::from_le_bytes(num_of_purchasers.try_into().unwrap()),
+            num_of_open_purchases: u64::from_le_bytes(num_of_open_purchases.try_into().unwrap()),
+            metadata_cid: metadata_cid.to_vec(),
+            vestings,
         })
-        .collect();
-
-    return Ok(OfferingAccount {
-        discriminator: discriminator[0],
-        state: state[0],
-        publisher: Pubkey::new_from_array((*publisher).try_into().unwrap()),
-        creator: Pubkey::new_from_array((*creator).try_into().unwrap()),
-        offering_seed: Pubkey::new_from_array((*offering_seed).try_into().unwrap()),
-        promoter: Pubkey::new_from_array((*promoter).try_into().unwrap()),
-        funded_mint: Pubkey::new_from_array((*funded_mint).try_into().unwrap()),
-        offered_mint: Pubkey::new_from_array((*offered_mint).try_into().unwrap()),
-        softcap: u64::from_le_bytes(softcap.try_into().unwrap()),
-        hardcap: u64::from_le_bytes(hardcap.try_into().unwrap()),
-        funded_amount: u64::from_le_bytes(funded_amount.try_into().unwrap()),
-        min_funding: u64::from_le_bytes(min_funding.try_into().unwrap()),
-        max_funding: u64::from_le_bytes(max_funding.try_into().unwrap()),
-        offer_quantity: u64::from_le_bytes(offer_quantity.try_into().unwrap()),
-        distributed_quantity: u64::from_le_bytes(distributed_quantity.try_into().unwrap()),
-        market_pool_percentage: market_pool_percentage[0],
-        publish_time: i64::from_le_bytes(publish_time.try_into().unwrap()),
-        open_time: i64::from_le_bytes(open_time.try_into().unwrap()),
-        close_time: i64::from_le_bytes(close_time.try_into().unwrap()),
-        exit_time: i64::from_le_bytes(exit_time.try_into().unwrap()),
-        num_of_purchasers: u64::from_le_bytes(num_of_purchasers.try_into().unwrap()),
-        metadata_cid: metadata_cid.to_vec(),
-        vestings,
-    });
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) -> Result<(), ProgramError> {
+        if dst.len() != self.get_packed_len() {
+            return Err(INVALID_OFFERING_LEN_ERROR);
+        }
+
+        let (discriminator, rest) = dst.split_at_mut(1);
+        let (version, rest) = rest.split_at_mut(1);
+        let (state, rest) = rest.split_at_mut(1);
+        let (publisher, rest) = rest.split_at_mut(32);
+        let (creator, rest) = rest.split_at_mut(32);
+        let (offering_seed, rest) = rest.split_at_mut(32);
+        let (promoter, rest) = rest.split_at_mut(32);
+        let (funded_mint, rest) = rest.split_at_mut(32);
+        let (offered_mint, rest) = rest.split_at_mut(32);
+        let (softcap, rest) = rest.split_at_mut(8);
+        let (hardcap, rest) = rest.split_at_mut(8);
+        let (funded_amount, rest) = rest.split_at_mut(8);
+        let (min_funding, rest) = rest.split_at_mut(8);
+        let (max_funding, rest) = rest.split_at_mut(8);
+        let (offer_quantity, rest) = rest.split_at_mut(8);
+        let (distributed_quantity, rest) = rest.split_at_mut(8);
+        let (market_pool_percentage, rest) = rest.split_at_mut(1);
+        let (publish_time, rest) = rest.split_at_mut(8);
+        let (open_time, rest) = rest.split_at_mut(8);
+        let (close_time, rest) = rest.split_at_mut(8);
+        let (exit_time, rest) = rest.split_at_mut(8);
+        let (num_of_purchasers, rest) = rest.split_at_mut(8);
+        let (num_of_open_purchases, rest) = rest.split_at_mut(8);
+        let (metadata_len, rest) = rest.split_at_mut(8);
+        let (num_of_vestings, rest) = rest.split_at_mut(1);
+
+        discriminator[0] = OFFERING_PDA_TYPE;
+        version[0] = ACCOUNT_VERSION;
+        state[0] = self.state | OFFERING_IS_INITIALIZED;
+        publisher.copy_from_slice(self.publisher.as_ref());
+        creator.copy_from_slice(self.creator.as_ref());
+        offering_seed.copy_from_slice(self.offering_seed.as_ref());
+        promoter.copy_from_slice(self.promoter.as_ref());
+        funded_mint.copy_from_slice(self.funded_mint.as_ref());
+        offered_mint.copy_from_slice(self.offered_mint.as_ref());
+        softcap.copy_from_slice(&self.softcap.to_le_bytes());
+        hardcap.copy_from_slice(&self.hardcap.to_le_bytes());
+        funded_amount.copy_from_slice(&self.funded_amount.to_le_bytes());
+        min_funding.copy_from_slice(&self.min_funding.to_le_bytes());
+        max_funding.copy_from_slice(&self.max_funding.to_le_bytes());
+        offer_quantity.copy_from_slice(&self.offer_quantity.to_le_bytes());
+        distributed_quantity.copy_from_slice(&self.distributed_quantity.to_le_bytes());
+        market_pool_percentage[0] = self.market_pool_percentage;
+        publish_time.copy_from_slice(&self.publish_time.to_le_bytes());
+        open_time.copy_from_slice(&self.open_time.to_le_bytes());
+        close_time.copy_from_slice(&self.close_time.to_le_bytes());
+        exit_time.copy_from_slice(&self.exit_time.to_le_bytes());
+        num_of_purchasers.copy_from_slice(&self.num_of_purchasers.to_le_bytes());
+        num_of_open_purchases.copy_from_slice(&self.num_of_open_purchases.to_le_bytes());
+        metadata_len.copy_from_slice(&self.metadata_cid.len().to_le_bytes());
+        num_of_vestings[0] = self.vestings.len() as u8;
+
+        let (metadata_cid, rest) = rest.split_at_mut(self.metadata_cid.len());
+        metadata_cid.copy_from_slice(&self.metadata_cid);
+
+        let mut vesting_dst = rest;
+
+        for vesting in self.vestings.iter() {
+            let (release_time, rest) = vesting_dst.split_at_mut(8);
+            let (percentage, rest) = rest.split_at_mut(1);
+
+            release_time.copy_from_slice(&vesting.release_time.to_le_bytes());
+            percentage[0] = vesting.percentage;
+
+            vesting_dst = rest;
+        }
+
+        Ok(())
+    }
 }
 
-pub fn pack_offering_account(
-    dst: &mut [u8],
-    offering: &OfferingAccount,
-) -> Result<(), ProgramError> {
-    let (discriminator, rest) = dst.split_at_mut(1);
-    let (state, rest) = rest.split_at_mut(1);
-    let (publisher, rest) = rest.split_at_mut(32);
-    let (creator, rest) = rest.split_at_mut(32);
-    let (offering_seed, rest) = rest.split_at_mut(32);
-    let (promoter, rest) = rest.split_at_mut(32);
-    let (funded_mint, rest) = rest.split_at_mut(32);
-    let (offered_mint, rest) = rest.split_at_mut(32);
-    let (softcap, rest) = rest.split_at_mut(8);
-    let (hardcap, rest) = rest.split_at_mut(8);
-    let (funded_amount, rest) = rest.split_at_mut(8);
-    let (min_funding, rest) = rest.split_at_mut(8);
-    let (max_funding, rest) = rest.split_at_mut(8);
-    let (offer_quantity, rest) = rest.split_at_mut(8);
-    let (distributed_quantity, rest) = rest.split_at_mut(8);
-    let (market_pool_percentage, rest) = rest.split_at_mut(1);
-    let (publish_time, rest) = rest.split_at_mut(8);
-    let (open_time, rest) = rest.split_at_mut(8);
-    let (close_time, rest) = rest.split_at_mut(8);
-    let (exit_time, rest) = rest.split_at_mut(8);
-    let (num_of_purchasers, rest) = rest.split_at_mut(8);
-    let (metadata_len, rest) = rest.split_at_mut(8);
-    let (num_of_vestings, rest) = rest.split_at_mut(1);
-
-    discriminator[0] = OFFERING_PDA_TYPE;
-    state[0] = offering.state | OFFERING_IS_INITIALIZED;
-    publisher.copy_from_slice(offering.publisher.as_ref());
-    creator.copy_from_slice(offering.creator.as_ref());
-    offering_seed.copy_from_slice(offering.offering_seed.as_ref());
-    promoter.copy_from_slice(offering.promoter.as_ref());
-    funded_mint.copy_from_slice(offering.funded_mint.as_ref());
-    offered_mint.copy_from_slice(offering.offered_mint.as_ref());
-    softcap.copy_from_slice(&offering.softcap.to_le_bytes());
-    hardcap.copy_from_slice(&offering.hardcap.to_le_bytes());
-    funded_amount.copy_from_slice(&offering.funded_amount.to_le_bytes());
-    min_funding.copy_from_slice(&offering.min_funding.to_le_bytes());
-    max_funding.copy_from_slice(&offering.max_funding.to_le_bytes());
-    offer_quantity.copy_from_slice(&offering.offer_quantity.to_le_bytes());
-    distributed_quantity.copy_from_slice(&offering.distributed_quantity.to_le_bytes());
-    market_pool_percentage[0] = offering.market_pool_percentage;
-    publish_time.copy_from_slice(&offering.publish_time.to_le_bytes());
-    open_time.copy_from_slice(&offering.open_time.to_le_bytes());
-    close_time.copy_from_slice(&offering.close_time.to_le_bytes());
-    exit_time.copy_from_slice(&offering.exit_time.to_le_bytes());
-    num_of_purchasers.copy_from_slice(&offering.num_of_purchasers.to_le_bytes());
-    metadata_len.copy_from_slice(&offering.metadata_cid.len().to_le_bytes());
-    num_of_vestings[0] = offering.vestings.len() as u8;
-
-    let (metadata_cid, rest) = rest.split_at_mut(offering.metadata_cid.len());
-    metadata_cid.copy_from_slice(&offering.metadata_cid);
-
-    let mut vesting_dst = rest;
-
-    for vesting in offering.vestings.iter() {
-        let (release_time, rest) = vesting_dst.split_at_mut(8);
-        let (percentage, rest) = rest.split_at_mut(1);
-
-        release_time.copy_from_slice(&vesting.release_time.to_le_bytes());
-        percentage[0] = vesting.percentage;
-
-        vesting_dst = rest;
-    }
-
-    return Ok(());
-}
\ No newline at end of file
+/// Reads and writes the fixed fields of an offering account in place, without
+/// deserializing `metadata_cid` and `vestings`. Meant for the hot paths that
+/// only touch a few counters, e.g. a purchase. The layout is validated once
+/// when the view is created.
+pub struct OfferingAccountView<D> {
+    data: D,
+}
+
+impl<D: Deref<Target = [u8]>> OfferingAccountView<D> {
+    pub fn new(data: D) -> Result<Self, ProgramError> {
+        check_offering_account_layout(&data)?;
+
+        if (data[STATE_OFFSET] & OFFERING_IS_INITIALIZED) != OFFERING_IS_INITIALIZED {
+            return Err(INVALID_OFFERING_TYPE_ERROR);
+        }
+
+        Ok(OfferingAccountView { data })
+    }
+
+    fn read_pubkey(&self, offset: usize) -> Pubkey {
+        Pubkey::new_from_array(self.data[offset..offset + 32].try_into().unwrap())
+    }
+
+    fn read_u64(&self, offset: usize) -> u64 {
+        u64::from_le_bytes(self.data[offset..offset + 8].try_into().unwrap())
+    }
+
+    fn read_i64(&self, offset: usize) -> i64 {
+        i64::from_le_bytes(self.data[offset..offset + 8].try_into().unwrap())
+    }
+
+    pub fn version(&self) -> u8 {
+        self.data[VERSION_OFFSET]
+    }
+
+    pub fn state(&self) -> u8 {
+        self.data[STATE_OFFSET]
+    }
+
+    pub fn publisher(&self) -> Pubkey {
+        self.read_pubkey(PUBLISHER_OFFSET)
+    }
+
+    pub fn creator(&self) -> Pubkey {
+        self.read_pubkey(CREATOR_OFFSET)
+    }
+
+    pub fn offering_seed(&self) -> Pubkey {
+        self.read_pubkey(OFFERING_SEED_OFFSET)
+    }
+
+    pub fn promoter(&self) -> Pubkey {
+        self.read_pubkey(PROMOTER_OFFSET)
+    }
+
+    pub fn funded_mint(&self) -> Pubkey {
+        self.read_pubkey(FUNDED_MINT_OFFSET)
+    }
+
+    pub fn offered_mint(&self) -> Pubkey {
+        self.read_pubkey(OFFERED_MINT_OFFSET)
+    }
+
+    pub fn softcap(&self) -> u64 {
+        self.read_u64(SOFTCAP_OFFSET)
+    }
+
+    pub fn hardcap(&self) -> u64 {
+        self.read_u64(HARDCAP_OFFSET)
+    }
+
+    pub fn funded_amount(&self) -> u64 {
+        self.read_u64(FUNDED_AMOUNT_OFFSET)
+    }
+
+    pub fn min_funding(&self) -> u64 {
+        self.read_u64(MIN_FUNDING_OFFSET)
+    }
+
+    pub fn max_funding(&self) -> u64 {
+        self.read_u64(MAX_FUNDING_OFFSET)
+    }
+
+    pub fn offer_quantity(&self) -> u64 {
+        self.read_u64(OFFER_QUANTITY_OFFSET)
+    }
+
+    pub fn distributed_quantity(&self) -> u64 {
+        self.read_u64(DISTRIBUTED_QUANTITY_OFFSET)
+    }
+
+    pub fn market_pool_percentage(&self) -> u8 {
+        self.data[MARKET_POOL_PERCENTAGE_OFFSET]
+    }
+
+    pub fn publish_time(&self) -> i64 {
+        self.read_i64(PUBLISH_TIME_OFFSET)
+    }
+
+    pub fn open_time(&self) -> i64 {
+        self.read_i64(OPEN_TIME_OFFSET)
+    }
+
+    pub fn close_time(&self) -> i64 {
+        self.read_i64(CLOSE_TIME_OFFSET)
+    }
+
+    pub fn exit_time(&self) -> i64 {
+        self.read_i64(EXIT_TIME_OFFSET)
+    }
+
+    pub fn num_of_purchasers(&self) -> u64 {
+        self.read_u64(NUM_OF_PURCHASERS_OFFSET)
+    }
+
+    pub fn num_of_open_purchases(&self) -> u64 {
+        self.read_u64(NUM_OF_OPEN_PURCHASES_OFFSET)
+    }
+
+    pub fn metadata_cid(&self) -> &[u8] {
+        let len = self.read_u64(METADATA_CID_LEN_OFFSET) as usize;
+        &self.data[FIXED_OFFERING_ACCOUNT_DATA_LEN..FIXED_OFFERING_ACCOUNT_DATA_LEN + len]
+    }
+
+    pub fn vestings(&self) -> impl Iterator<Item = OfferingVesting> + '_ {
+        let start = FIXED_OFFERING_ACCOUNT_DATA_LEN + self.metadata_cid().len();
+        self.data[start..]
+            .chunks_exact(OFFERING_VESTING_DATA_LEN)
+            .map(|vesting_src| OfferingVesting {
+                release_time: i64::from_le_bytes(vesting_src[0..8].try_into().unwrap()),
+                percentage: vesting_src[8],
+            })
+    }
+
+    pub fn is_purchasable(&self, time: i64) -> bool {
+        (self.state() & OFFERING_IS_INITIALIZED) == OFFERING_IS_INITIALIZED
+            && time > self.open_time()
+            && time < self.close_time()
+            && self.hardcap() > self.funded_amount()
+    }
+
+    pub fn is_approved(&self) -> bool {
+        (self.state() & OFFERING_IS_APPROVED_BY_PUBLISHER) == OFFERING_IS_APPROVED_BY_PUBLISHER
+    }
+
+    pub fn is_offer_provided(&self) -> bool {
+        (self.state() & OFFERING_OFFER_IS_PROVIDED) == OFFERING_OFFER_IS_PROVIDED
+    }
+
+    pub fn has_sold_out(&self) -> bool {
+        (self.state() & OFFERING_HAS_SOLD_OUT) == OFFERING_HAS_SOLD_OUT
+    }
+
+    // the view trusts the layout only, so a funded amount above the hardcap must not underflow
+    pub fn fundable_amount(&self) -> u64 {
+        self.hardcap().saturating_sub(self.funded_amount())
+    }
+
+    pub fn offer_quantity_for(&self, funded_amount: u64) -> Option<u64> {
+        calc_offer_quantity(funded_amount, self.offer_quantity(), self.hardcap())
+    }
+}
+
+impl<D: DerefMut<Target = [u8]>> OfferingAccountView<D> {
+    fn write_u64(&mut self, offset: usize, value: u64) {
+        self.data[offset..offset + 8].copy_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn set_state(&mut self, state: u8) {
+        self.data[STATE_OFFSET] = state | OFFERING_IS_INITIALIZED;
+    }
+
+    pub fn set_funded_amount(&mut self, funded_amount: u64) {
+        self.write_u64(FUNDED_AMOUNT_OFFSET, funded_amount);
+    }
+
+    pub fn set_distributed_quantity(&mut self, distributed_quantity: u64) {
+        self.write_u64(DISTRIBUTED_QUANTITY_OFFSET, distributed_quantity);
+    }
+
+    pub fn set_num_of_purchasers(&mut self, num_of_purchasers: u64) {
+        self.write_u64(NUM_OF_PURCHASERS_OFFSET, num_of_purchasers);
+    }
+
+    pub fn set_num_of_open_purchases(&mut self, num_of_open_purchases: u64) {
+        self.write_u64(NUM_OF_OPEN_PURCHASES_OFFSET, num_of_open_purchases);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn offering() -> OfferingAccount {
+        OfferingAccount {
+            discriminator: OFFERING_PDA_TYPE,
+            version: ACCOUNT_VERSION,
+            state: OFFERING_IS_INITIALIZED | OFFERING_IS_APPROVED_BY_PUBLISHER,
+            publisher: Pubkey::new_unique(),
+            creator: Pubkey::new_unique(),
+            offering_seed: Pubkey::new_unique(),
+            promoter: Pubkey::new_unique(),
+            funded_mint: Pubkey::new_unique(),
+            offered_mint: Pubkey::new_unique(),
+            softcap: 1_000,
+            hardcap: 5_000,
+            funded_amount: 1_250,
+            min_funding: 10,
+            max_funding: 2_000,
+            offer_quantity: 10_000,
+            distributed_quantity: 700,
+            market_pool_percentage: 20,
+            publish_time: 10,
+            open_time: 20,
+            close_time: 30,
+            exit_time: 40,
+            num_of_purchasers: 7,
+            num_of_open_purchases: 3,
+            metadata_cid: b"bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi".to_vec(),
+            vestings: vec![
+                OfferingVesting {
+                    release_time: 50,
+                    percentage: 40,
+                },
+                OfferingVesting {
+                    release_time: 60,
+                    percentage: 60,
+                },
+            ],
+        }
+    }
+
+    fn packed(offering: &OfferingAccount) -> Vec<u8> {
+        let mut data = vec![0; offering.get_packed_len()];
+        offering.pack(&mut data).unwrap();
+        data
+    }
+
+    #[test]
+    fn test_pack_unpack_roundtrip() {
+        let empty = OfferingAccount {
+            metadata_cid: vec![],
+            vestings: vec![],
+            ..offering()
+        };
+        let full = OfferingAccount {
+            metadata_cid: vec![u8::MAX; MAX_METADATA_CID_LEN],
+            vestings: vec![
+                OfferingVesting {
+                    release_time: i64::MAX,
+                    percentage: 1,
+                };
+                MAX_NUM_OF_VESTINGS
+            ],
+            ..offering()
+        };
+
+        for offering in [offering(), empty, full] {
+            let data = packed(&offering);
+            assert_eq!(data.len(), offering.get_packed_len());
+            assert_eq!(OfferingAccount::unpack(&data), Ok(offering));
+        }
+    }
+
+    #[test]
+    fn test_view_matches_unpack() {
+        let empty = OfferingAccount {
+            metadata_cid: vec![],
+            vestings: vec![],
+            ..offering()
+        };
+        let sold_out = OfferingAccount {
+            state: OFFERING_IS_INITIALIZED | OFFERING_OFFER_IS_PROVIDED | OFFERING_HAS_SOLD_OUT,
+            funded_amount: 5_000,
+            ..offering()
+        };
+
+        for offering in [offering(), empty, sold_out] {
+            let data = packed(&offering);
+            let view = OfferingAccountView::new(&data[..]).unwrap();
+            let unpacked = OfferingAccount::unpack(&data).unwrap();
+
+            assert_eq!(view.state(), unpacked.state);
+            assert_eq!(view.publisher(), unpacked.publisher);
+            assert_eq!(view.creator(), unpacked.creator);
+            assert_eq!(view.offering_seed(), unpacked.offering_seed);
+            assert_eq!(view.promoter(), unpacked.promoter);
+            assert_eq!(view.funded_mint(), unpacked.funded_mint);
+            assert_eq!(view.offered_mint(), unpacked.offered_mint);
+            assert_eq!(view.softcap(), unpacked.softcap);
+            assert_eq!(view.hardcap(), unpacked.hardcap);
+            assert_eq!(view.funded_amount(), unpacked.funded_amount);
+            assert_eq!(view.min_funding(), unpacked.min_funding);
+            assert_eq!(view.max_funding(), unpacked.max_funding);
+            assert_eq!(view.offer_quantity(), unpacked.offer_quantity);
+            assert_eq!(view.distributed_quantity(), unpacked.distributed_quantity);
+            assert_eq!(
+                view.market_pool_percentage(),
+                unpacked.market_pool_percentage
+            );
+            assert_eq!(view.publish_time(), unpacked.publish_time);
+            assert_eq!(view.open_time(), unpacked.open_time);
+            assert_eq!(view.close_time(), unpacked.close_time);
+            assert_eq!(view.exit_time(), unpacked.exit_time);
+            assert_eq!(view.num_of_purchasers(), unpacked.num_of_purchasers);
+            assert_eq!(view.num_of_open_purchases(), unpacked.num_of_open_purchases);
+            assert_eq!(view.metadata_cid(), &unpacked.metadata_cid[..]);
+            assert_eq!(view.vestings().collect::<Vec<_>>(), unpacked.vestings);
+
+            assert_eq!(view.is_approved(), unpacked.is_approved());
+            assert_eq!(view.is_offer_provided(), unpacked.is_offer_provided());
+            assert_eq!(view.has_sold_out(), unpacked.has_sold_out());
+            assert_eq!(view.fundable_amount(), unpacked.fundable_amount());
+            for time in [0, 20, 25, 30, 100] {
+                assert_eq!(view.is_purchasable(time), unpacked.is_purchasable(time));
+            }
+            for funded_amount in [0, 1, 990, u64::MAX] {
+                assert_eq!(
+                    view.offer_quantity_for(funded_amount),
+                    unpacked.offer_quantity_for(funded_amount)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_view_setters_match_pack() {
+        let mut expected = offering();
+        let mut data = packed(&expected);
+
+        let mut view = OfferingAccountView::new(&mut data[..]).unwrap();
+        view.set_state(OFFERING_HAS_SOLD_OUT);
+        view.set_funded_amount(5_000);
+        view.set_distributed_quantity(9_000);
+        view.set_num_of_purchasers(8);
+        view.set_num_of_open_purchases(2);
+
+        expected.state = OFFERING_IS_INITIALIZED | OFFERING_HAS_SOLD_OUT;
+        expected.funded_amount = 5_000;
+        expected.distributed_quantity = 9_000;
+        expected.num_of_purchasers = 8;
+        expected.num_of_open_purchases = 2;
+        assert_eq!(data, packed(&expected));
+    }
+
+    #[test]
+    fn test_fundable_amount_does_not_underflow() {
+        let offering = OfferingAccount {
+            hardcap: 5_000,
+            funded_amount: 5_001,
+            ..offering()
+        };
+        let mut data = packed(&offering);
+        let view = OfferingAccountView::new(&mut data[..]).unwrap();
+
+        assert_eq!(offering.fundable_amount(), 0);
+        assert_eq!(view.fundable_amount(), 0);
+    }
+
+    #[test]
+    fn test_check_layout_rejects_malformed_data() {
+        let data = packed(&offering());
+        assert_eq!(check_offering_account_layout(&data), Ok((59, 2)));
+
+        for len in [0, FIXED_OFFERING_ACCOUNT_DATA_LEN - 1, data.len() - 1] {
+            assert_eq!(
+                check_offering_account_layout(&data[..len]),
+                Err(INVALID_OFFERING_LEN_ERROR)
+            );
+        }
+
+        let mut trailing = data.clone();
+        trailing.push(0);
+        assert_eq!(
+            check_offering_account_layout(&trailing),
+            Err(INVALID_OFFERING_LEN_ERROR)
+        );
+
+        // a cid length that would overflow the offsets of the variable part
+        for metadata_cid_len in [MAX_METADATA_CID_LEN as u64 + 1, u64::MAX] {
+            let mut oversized = data.clone();
+            oversized[METADATA_CID_LEN_OFFSET..METADATA_CID_LEN_OFFSET + 8]
+                .copy_from_slice(&metadata_cid_len.to_le_bytes());
+            assert_eq!(
+                check_offering_account_layout(&oversized),
+                Err(INVALID_OFFERING_LEN_ERROR)
+            );
+        }
+
+        let mut oversized = data.clone();
+        oversized[NUM_OF_VESTINGS_OFFSET] = MAX_NUM_OF_VESTINGS as u8 + 1;
+        oversized.resize(
+            data.len() + (MAX_NUM_OF_VESTINGS - 1) * OFFERING_VESTING_DATA_LEN,
+            0,
+        );
+        assert_eq!(
+            check_offering_account_layout(&oversized),
+            Err(INVALID_NUM_OF_VESTINGS_ERROR)
+        );
+
+        let mut wrong_type = data;
+        wrong_type[0] = OFFERING_PDA_TYPE + 1;
+        assert_eq!(
+            check_offering_account_layout(&wrong_type),
+            Err(INVALID_OFFERING_TYPE_ERROR)
+        );
+    }
+
+    #[test]
+    fn test_unpack_and_view_reject_malformed_data() {
+        let data = packed(&offering());
+
+        let mut trailing = data.clone();
+        trailing.push(0);
+        for malformed in [&data[..data.len() - 1], &trailing[..]] {
+            assert!(OfferingAccount::unpack(malformed).is_err());
+            assert!(OfferingAccountView::new(malformed).is_err());
+        }
+    }
+}
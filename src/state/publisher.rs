@@ -4,23 +4,40 @@ use solana_program::{
     pubkey::Pubkey,
 };
 
-use crate::{constant::PUBLISHER_PDA_TYPE, error::INVALID_PUBLISHER_LEN_ERROR};
+use crate::{
+    constant::{ACCOUNT_VERSION, PUBLISHER_PDA_TYPE},
+    error::INVALID_PUBLISHER_LEN_ERROR,
+    state::split_version,
+};
 
 pub const PUBLISHER_IS_INITIALIZED: u8 = 0b00000001;
 pub const PUBLISHER_IS_OPEN_FOR_OFFERING_CREATION: u8 = 0b00000010;
+// set when a legacy publisher is migrated, the publisher tokens and offerings created
+// before that are missing from the counters so the publisher can never be closed
+pub const PUBLISHER_HAS_UNCOUNTED_ACCOUNTS: u8 = 0b00000100;
+
+// a publisher packed before the version byte and the counters were added
+pub const LEGACY_PUBLISHER_LEN: usize = PublisherAccount::LEN - 1 - 8 - 8;
 
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub struct PublisherAccount {
     pub discriminator: u8,
+    pub version: u8,
     pub state: u8,
     pub creator: Pubkey,
     pub publisher_seed: Pubkey,
     pub update_authority: Pubkey,
     // when doing transfer of authority, the the transfer authority should be set to the new authority
     // then the new authority can accept the transfer of authority
+    // while it is pending, the current authority can cancel it by claiming the authority back
     pub transfer_authority: Pubkey,
     pub approval_authority: Pubkey,
+    // publisher tokens that are not closed yet, the publisher can only be closed without any
+    pub num_of_publisher_tokens: u64,
+    // offerings that are not closed yet, their purchases are derived from the offering address
+    // so the publisher can only be closed without any
+    pub num_of_offerings: u64,
 }
 
 impl PublisherAccount {
@@ -28,6 +45,10 @@ impl PublisherAccount {
         (self.state & PUBLISHER_IS_OPEN_FOR_OFFERING_CREATION)
             == PUBLISHER_IS_OPEN_FOR_OFFERING_CREATION
     }
+
+    pub fn has_uncounted_accounts(&self) -> bool {
+        (self.state & PUBLISHER_HAS_UNCOUNTED_ACCOUNTS) == PUBLISHER_HAS_UNCOUNTED_ACCOUNTS
+    }
 }
 
 impl Sealed for PublisherAccount {}
@@ -39,34 +60,59 @@ impl IsInitialized for PublisherAccount {
     }
 }
 
+#[allow(clippy::identity_op, clippy::len_zero, clippy::nonminimal_bool)]
 impl Pack for PublisherAccount {
     const LEN: usize = 0
       + 1 // discriminator
+      + 1 // version
       + 1 // state
       + 32 // creator
       + 32 // publisher_seed
       + 32 // update_authority
       + 32 // transfer_authority
       + 32 // approval_authority
+      + 8 // num_of_publisher_tokens
+      + 8 // num_of_offerings
       ;
 
     fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        let is_legacy = src.len() == LEGACY_PUBLISHER_LEN;
+        if src.len() != Self::LEN && !is_legacy {
+            return Err(INVALID_PUBLISHER_LEN_ERROR);
+        }
+
         let (discriminator, rest) = src.split_at(1);
+        let (version, rest) = split_version(rest, is_legacy);
         let (state, rest) = rest.split_at(1);
         let (creator, rest) = rest.split_at(32);
         let (publisher_seed, rest) = rest.split_at(32);
         let (update_authority, rest) = rest.split_at(32);
         let (transfer_authority, rest) = rest.split_at(32);
         let (approval_authority, rest) = rest.split_at(32);
+        // legacy publishers predate the counters, they start at zero once they are migrated and
+        // publisher tokens or offerings created before that are not counted
+        let (num_of_publisher_tokens, rest) = if is_legacy {
+            (&[0; 8][..], rest)
+        } else {
+            rest.split_at(8)
+        };
+        let (num_of_offerings, rest) = if is_legacy {
+            (&[0; 8][..], rest)
+        } else {
+            rest.split_at(8)
+        };
 
         if false
             || discriminator.len() != 1
+            || version.len() != 1
             || state.len() != 1
             || creator.len() != 32
             || publisher_seed.len() != 32
             || update_authority.len() != 32
             || transfer_authority.len() != 32
             || approval_authority.len() != 32
+            || num_of_publisher_tokens.len() != 8
+            || num_of_offerings.len() != 8
             || rest.len() != 0
         {
             return Err(INVALID_PUBLISHER_LEN_ERROR);
@@ -74,43 +120,57 @@ impl Pack for PublisherAccount {
 
         Ok(PublisherAccount {
             discriminator: discriminator[0],
+            version: version[0],
             state: state[0],
             creator: Pubkey::new_from_array((*creator).try_into().unwrap()),
             publisher_seed: Pubkey::new_from_array((*publisher_seed).try_into().unwrap()),
             update_authority: Pubkey::new_from_array((*update_authority).try_into().unwrap()),
             transfer_authority: Pubkey::new_from_array((*transfer_authority).try_into().unwrap()),
             approval_authority: Pubkey::new_from_array((*approval_authority).try_into().unwrap()),
+            num_of_publisher_tokens: u64::from_le_bytes(
+                (*num_of_publisher_tokens).try_into().unwrap(),
+            ),
+            num_of_offerings: u64::from_le_bytes((*num_of_offerings).try_into().unwrap()),
         })
     }
 
     fn pack_into_slice(&self, dst: &mut [u8]) {
         let (discriminator, rest) = dst.split_at_mut(1);
+        let (version, rest) = rest.split_at_mut(1);
         let (state, rest) = rest.split_at_mut(1);
         let (creator, rest) = rest.split_at_mut(32);
         let (publisher_seed, rest) = rest.split_at_mut(32);
         let (update_authority, rest) = rest.split_at_mut(32);
         let (transfer_authority, rest) = rest.split_at_mut(32);
         let (approval_authority, rest) = rest.split_at_mut(32);
+        let (num_of_publisher_tokens, rest) = rest.split_at_mut(8);
+        let (num_of_offerings, rest) = rest.split_at_mut(8);
 
         if false
             || discriminator.len() != 1
+            || version.len() != 1
             || state.len() != 1
             || creator.len() != 32
             || publisher_seed.len() != 32
             || update_authority.len() != 32
             || transfer_authority.len() != 32
             || approval_authority.len() != 32
+            || num_of_publisher_tokens.len() != 8
+            || num_of_offerings.len() != 8
             || rest.len() != 0
         {
             return;
         }
 
         discriminator[0] = PUBLISHER_PDA_TYPE;
+        version[0] = ACCOUNT_VERSION;
         state[0] = self.state | PUBLISHER_IS_INITIALIZED;
         creator.copy_from_slice(self.creator.as_ref());
         publisher_seed.copy_from_slice(self.publisher_seed.as_ref());
         update_authority.copy_from_slice(self.update_authority.as_ref());
         transfer_authority.copy_from_slice(self.transfer_authority.as_ref());
         approval_authority.copy_from_slice(self.approval_authority.as_ref());
+        num_of_publisher_tokens.copy_from_slice(&self.num_of_publisher_tokens.to_le_bytes());
+        num_of_offerings.copy_from_slice(&self.num_of_offerings.to_le_bytes());
     }
-}
\ No newline at end of file
+}
@@ -4,7 +4,10 @@ use solana_program::{
     pubkey::Pubkey,
 };
 
-use crate::{constant::PUBLISHER_TOKEN_PDA_TYPE, error::INVALID_PUBLISHER_TOKEN_LEN_ERROR};
+use crate::{
+    constant::{ACCOUNT_VERSION, PUBLISHER_TOKEN_PDA_TYPE},
+    error::INVALID_PUBLISHER_TOKEN_LEN_ERROR,
+};
 
 pub const PUBLISHER_TOKEN_IS_INITIALIZED: u8 = 0b00000001;
 pub const ALLOW_TOKEN_AS_OFFER_CREATION_PAYMENT: u8 = 0b00000010;
@@ -12,17 +15,41 @@ pub const ALLOW_TOKEN_AS_OFFERS: u8 = 0b00000100;
 pub const ALLOW_TOKEN_AS_FUNDING: u8 = 0b00001000;
 pub const PUBLISHER_TOKEN_IS_DISABLED: u8 = 0b00010000;
 
+// the bits the publisher update authority is allowed to set
+pub const PUBLISHER_TOKEN_CONFIGURABLE_STATE: u8 = ALLOW_TOKEN_AS_OFFER_CREATION_PAYMENT
+    | ALLOW_TOKEN_AS_OFFERS
+    | ALLOW_TOKEN_AS_FUNDING
+    | PUBLISHER_TOKEN_IS_DISABLED;
+
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub struct PublisherTokenAccount {
     pub discriminator: u8,
+    pub version: u8,
     pub state: u8,
     pub publisher: Pubkey,
     pub mint: Pubkey,
     pub offer_creation_price: u64,
 }
 
-impl PublisherTokenAccount {}
+impl PublisherTokenAccount {
+    pub fn is_disabled(&self) -> bool {
+        (self.state & PUBLISHER_TOKEN_IS_DISABLED) == PUBLISHER_TOKEN_IS_DISABLED
+    }
+
+    pub fn is_allowed_as_offer_creation_payment(&self) -> bool {
+        (self.state & ALLOW_TOKEN_AS_OFFER_CREATION_PAYMENT)
+            == ALLOW_TOKEN_AS_OFFER_CREATION_PAYMENT
+    }
+
+    pub fn is_allowed_as_offers(&self) -> bool {
+        (self.state & ALLOW_TOKEN_AS_OFFERS) == ALLOW_TOKEN_AS_OFFERS
+    }
+
+    pub fn is_allowed_as_funding(&self) -> bool {
+        (self.state & ALLOW_TOKEN_AS_FUNDING) == ALLOW_TOKEN_AS_FUNDING
+    }
+}
 
 impl Sealed for PublisherTokenAccount {}
 
@@ -33,9 +60,11 @@ impl IsInitialized for PublisherTokenAccount {
     }
 }
 
+#[allow(clippy::identity_op, clippy::len_zero, clippy::nonminimal_bool)]
 impl Pack for PublisherTokenAccount {
     const LEN: usize = 0
         + 1 // Discriminator
+        + 1 // Version
         + 1 // State
         + 32 // Publisher
         + 32 // Mint
@@ -43,7 +72,12 @@ impl Pack for PublisherTokenAccount {
         ;
 
     fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        if src.len() != Self::LEN {
+            return Err(INVALID_PUBLISHER_TOKEN_LEN_ERROR);
+        }
+
         let (discriminator, rest) = src.split_at(1);
+        let (version, rest) = rest.split_at(1);
         let (state, rest) = rest.split_at(1);
         let (publisher, rest) = rest.split_at(32);
         let (mint, rest) = rest.split_at(32);
@@ -51,6 +85,7 @@ impl Pack for PublisherTokenAccount {
 
         if false
             || discriminator.len() != 1
+            || version.len() != 1
             || state.len() != 1
             || publisher.len() != 32
             || mint.len() != 32
@@ -62,6 +97,7 @@ impl Pack for PublisherTokenAccount {
 
         Ok(PublisherTokenAccount {
             discriminator: discriminator[0],
+            version: version[0],
             state: state[0],
             publisher: Pubkey::new_from_array((*publisher).try_into().unwrap()),
             mint: Pubkey::new_from_array((*mint).try_into().unwrap()),
@@ -71,6 +107,7 @@ impl Pack for PublisherTokenAccount {
 
     fn pack_into_slice(&self, dst: &mut [u8]) {
         let (discriminator_dst, dst) = dst.split_at_mut(1);
+        let (version_dst, dst) = dst.split_at_mut(1);
         let (state_dst, dst) = dst.split_at_mut(1);
         let (publisher_dst, dst) = dst.split_at_mut(32);
         let (mint_dst, dst) = dst.split_at_mut(32);
@@ -78,6 +115,7 @@ impl Pack for PublisherTokenAccount {
 
         if false
             || discriminator_dst.len() != 1
+            || version_dst.len() != 1
             || state_dst.len() != 1
             || publisher_dst.len() != 32
             || mint_dst.len() != 32
@@ -88,9 +126,10 @@ impl Pack for PublisherTokenAccount {
         }
 
         discriminator_dst[0] = PUBLISHER_TOKEN_PDA_TYPE;
+        version_dst[0] = ACCOUNT_VERSION;
         state_dst[0] = self.state | PUBLISHER_TOKEN_IS_INITIALIZED;
         publisher_dst.copy_from_slice(self.publisher.as_ref());
         mint_dst.copy_from_slice(self.mint.as_ref());
         price_dst.copy_from_slice(&self.offer_creation_price.to_le_bytes());
     }
-}
\ No newline at end of file
+}
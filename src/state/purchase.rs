@@ -0,0 +1,114 @@
+use solana_program::{
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack, Sealed},
+    pubkey::Pubkey,
+};
+
+use crate::{
+    constant::{ACCOUNT_VERSION, PURCHASE_PDA_TYPE},
+    error::INVALID_PURCHASE_LEN_ERROR,
+};
+
+pub const PURCHASE_IS_INITIALIZED: u8 = 0b00000001;
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PurchaseAccount {
+    pub discriminator: u8,
+    pub version: u8,
+    pub state: u8,
+    pub offering: Pubkey,
+    pub purchaser: Pubkey,
+    pub funded_amount: u64,
+    pub purchased_quantity: u64,
+    pub distributed_quantity: u64,
+    // bit n is set once the n-th vesting of the offering has been distributed,
+    // which is why an offering can not have more than MAX_NUM_OF_VESTINGS vestings
+    pub distributed_vestings: u64,
+}
+
+impl PurchaseAccount {
+    pub fn is_vesting_distributed(&self, index: usize) -> bool {
+        index < 64 && (self.distributed_vestings & (1 << index)) != 0
+    }
+
+    pub fn is_all_vesting_distributed(&self) -> bool {
+        self.purchased_quantity == self.distributed_quantity
+    }
+}
+
+impl Sealed for PurchaseAccount {}
+
+impl IsInitialized for PurchaseAccount {
+    fn is_initialized(&self) -> bool {
+        self.discriminator == PURCHASE_PDA_TYPE
+            && (self.state & PURCHASE_IS_INITIALIZED) == PURCHASE_IS_INITIALIZED
+    }
+}
+
+impl Pack for PurchaseAccount {
+    const LEN: usize = 1 // discriminator
+        + 1 // version
+        + 1 // state
+        + 32 // offering
+        + 32 // purchaser
+        + 8 // funded_amount
+        + 8 // purchased_quantity
+        + 8 // distributed_quantity
+        + 8 // distributed_vestings
+        ;
+
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError> {
+        if src.len() != Self::LEN {
+            return Err(INVALID_PURCHASE_LEN_ERROR);
+        }
+
+        let (discriminator, rest) = src.split_at(1);
+        let (version, rest) = rest.split_at(1);
+        let (state, rest) = rest.split_at(1);
+        let (offering, rest) = rest.split_at(32);
+        let (purchaser, rest) = rest.split_at(32);
+        let (funded_amount, rest) = rest.split_at(8);
+        let (purchased_quantity, rest) = rest.split_at(8);
+        let (distributed_quantity, rest) = rest.split_at(8);
+        let distributed_vestings = rest;
+
+        Ok(PurchaseAccount {
+            discriminator: discriminator[0],
+            version: version[0],
+            state: state[0],
+            offering: Pubkey::new_from_array((*offering).try_into().unwrap()),
+            purchaser: Pubkey::new_from_array((*purchaser).try_into().unwrap()),
+            funded_amount: u64::from_le_bytes(funded_amount.try_into().unwrap()),
+            purchased_quantity: u64::from_le_bytes(purchased_quantity.try_into().unwrap()),
+            distributed_quantity: u64::from_le_bytes(distributed_quantity.try_into().unwrap()),
+            distributed_vestings: u64::from_le_bytes(distributed_vestings.try_into().unwrap()),
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        if dst.len() != Self::LEN {
+            return;
+        }
+
+        let (discriminator, rest) = dst.split_at_mut(1);
+        let (version, rest) = rest.split_at_mut(1);
+        let (state, rest) = rest.split_at_mut(1);
+        let (offering, rest) = rest.split_at_mut(32);
+        let (purchaser, rest) = rest.split_at_mut(32);
+        let (funded_amount, rest) = rest.split_at_mut(8);
+        let (purchased_quantity, rest) = rest.split_at_mut(8);
+        let (distributed_quantity, rest) = rest.split_at_mut(8);
+        let distributed_vestings = rest;
+
+        discriminator[0] = PURCHASE_PDA_TYPE;
+        version[0] = ACCOUNT_VERSION;
+        state[0] = self.state | PURCHASE_IS_INITIALIZED;
+        offering.copy_from_slice(self.offering.as_ref());
+        purchaser.copy_from_slice(self.purchaser.as_ref());
+        funded_amount.copy_from_slice(&self.funded_amount.to_le_bytes());
+        purchased_quantity.copy_from_slice(&self.purchased_quantity.to_le_bytes());
+        distributed_quantity.copy_from_slice(&self.distributed_quantity.to_le_bytes());
+        distributed_vestings.copy_from_slice(&self.distributed_vestings.to_le_bytes());
+    }
+}
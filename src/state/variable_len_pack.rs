@@ -0,0 +1,41 @@
+use solana_program::{
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Sealed},
+};
+
+/// Counterpart of `Pack` for accounts whose length depends on their content,
+/// e.g. the metadata and vestings of an offering. Since the length can not be
+/// a constant, every implementation validates it while (un)packing.
+pub trait VariableLenPack: Sealed + Sized {
+    /// Length of the packed account, which is the size it is allocated with.
+    fn get_packed_len(&self) -> usize;
+
+    #[doc(hidden)]
+    fn unpack_from_slice(src: &[u8]) -> Result<Self, ProgramError>;
+
+    #[doc(hidden)]
+    fn pack_into_slice(&self, dst: &mut [u8]) -> Result<(), ProgramError>;
+
+    /// Unpacks the account and checks that it is initialized.
+    fn unpack(input: &[u8]) -> Result<Self, ProgramError>
+    where
+        Self: IsInitialized,
+    {
+        let value = Self::unpack_unchecked(input)?;
+        if value.is_initialized() {
+            Ok(value)
+        } else {
+            Err(ProgramError::UninitializedAccount)
+        }
+    }
+
+    /// Unpacks the account without checking that it is initialized.
+    fn unpack_unchecked(input: &[u8]) -> Result<Self, ProgramError> {
+        Self::unpack_from_slice(input)
+    }
+
+    /// Packs the account into `dst`, which has to be exactly `get_packed_len` long.
+    fn pack(&self, dst: &mut [u8]) -> Result<(), ProgramError> {
+        self.pack_into_slice(dst)
+    }
+}
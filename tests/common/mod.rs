@@ -0,0 +1,569 @@
+//! In-process runtime for the integration tests. `solana-program-test` is not
+//! available for this version of `solana-program`, so the syscalls are stubbed:
+//! the clock and rent sysvars come from here, logged data and return data are
+//! kept per thread and cross program invocations are dispatched to the native
+//! processors of the contract, the market pool, Token-2022, the associated
+//! token account program and a minimal system program.
+//!
+//! Accounts are laid out like the runtime serializes them, so `realloc` and
+//! `assign` work, and they are leaked for the lifetime of the test.
+#![allow(dead_code)]
+
+use std::{cell::RefCell, collections::HashMap, sync::Once};
+
+use contract::{
+    constant::{ACCOUNT_VERSION, OFFERING_PDA_TYPE, PUBLISHER_PDA_TYPE},
+    pda::find_offering_address,
+    state::{
+        offering::{
+            OfferingAccount, OfferingVesting, OFFERING_IS_APPROVED_BY_PUBLISHER,
+            OFFERING_OFFER_IS_PROVIDED,
+        },
+        publisher::{PublisherAccount, PUBLISHER_IS_INITIALIZED},
+        variable_len_pack::VariableLenPack,
+    },
+};
+
+use solana_program::{
+    account_info::AccountInfo,
+    clock::Clock,
+    entrypoint::{ProgramResult, MAX_PERMITTED_DATA_INCREASE},
+    instruction::Instruction,
+    program_error::ProgramError,
+    program_pack::Pack,
+    program_stubs::{set_syscall_stubs, SyscallStubs},
+    pubkey::Pubkey,
+    rent::Rent,
+    system_program,
+};
+use spl_associated_token_account_client::address::get_associated_token_address_with_program_id;
+use spl_token_2022::{
+    extension::{
+        transfer_fee::{TransferFee, TransferFeeAmount, TransferFeeConfig},
+        BaseStateWithExtensions, BaseStateWithExtensionsMut, ExtensionType, StateWithExtensions,
+        StateWithExtensionsMut,
+    },
+    state::{Account, AccountState, Mint},
+};
+
+pub const SOL: u64 = 1_000_000_000;
+
+/// An approved offering with its offer escrowed, purchasable between the
+/// times 20 and 30 and vesting everything at 50.
+pub fn offering_fixture(funded_mint: &Pubkey, offered_mint: &Pubkey) -> OfferingAccount {
+    OfferingAccount {
+        discriminator: OFFERING_PDA_TYPE,
+        version: ACCOUNT_VERSION,
+        state: OFFERING_IS_APPROVED_BY_PUBLISHER | OFFERING_OFFER_IS_PROVIDED,
+        publisher: Pubkey::new_unique(),
+        creator: Pubkey::new_unique(),
+        offering_seed: Pubkey::new_unique(),
+        promoter: Pubkey::new_unique(),
+        funded_mint: *funded_mint,
+        offered_mint: *offered_mint,
+        softcap: 1_000,
+        hardcap: 5_000,
+        min_funding: 10,
+        max_funding: 2_000,
+        offer_quantity: 10_000,
+        publish_time: 10,
+        open_time: 20,
+        close_time: 30,
+        exit_time: 40,
+        metadata_cid: b"bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi".to_vec(),
+        vestings: vec![OfferingVesting {
+            release_time: 50,
+            percentage: 100,
+        }],
+        ..OfferingAccount::default()
+    }
+}
+
+#[derive(Default)]
+struct Context {
+    clock: Clock,
+    logs: Vec<Vec<Vec<u8>>>,
+    // the program currently executing, PDAs of the last one sign its invocations
+    program_stack: Vec<Pubkey>,
+    // set by the last program that returned data, cleared by every invocation
+    return_data: Option<(Pubkey, Vec<u8>)>,
+}
+
+thread_local! {
+    static CONTEXT: RefCell<Context> = RefCell::new(Context::default());
+}
+
+struct TestSyscallStubs;
+
+impl SyscallStubs for TestSyscallStubs {
+    fn sol_log(&self, _message: &str) {}
+
+    fn sol_log_data(&self, fields: &[&[u8]]) {
+        CONTEXT.with(|context| {
+            context
+                .borrow_mut()
+                .logs
+                .push(fields.iter().map(|field| field.to_vec()).collect())
+        });
+    }
+
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        let clock = CONTEXT.with(|context| context.borrow().clock.clone());
+        unsafe { *(var_addr as *mut Clock) = clock };
+        0
+    }
+
+    fn sol_set_return_data(&self, data: &[u8]) {
+        CONTEXT.with(|context| {
+            let mut context = context.borrow_mut();
+            let program_id = *context.program_stack.last().unwrap();
+            context.return_data = Some((program_id, data.to_vec()));
+        });
+    }
+
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        CONTEXT.with(|context| context.borrow().return_data.clone())
+    }
+
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        unsafe { *(var_addr as *mut Rent) = Rent::default() };
+        0
+    }
+
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        let caller = CONTEXT.with(|context| *context.borrow().program_stack.last().unwrap());
+        let pda_signers = signers_seeds
+            .iter()
+            .map(|seeds| Pubkey::create_program_address(seeds, &caller))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| ProgramError::InvalidSeeds)?;
+
+        let mut infos = Vec::with_capacity(instruction.accounts.len());
+        for meta in instruction.accounts.iter() {
+            let mut info = account_infos
+                .iter()
+                .find(|info| info.key.eq(&meta.pubkey))
+                .ok_or(ProgramError::NotEnoughAccountKeys)?
+                .clone();
+            if meta.is_signer && !info.is_signer && !pda_signers.contains(&meta.pubkey) {
+                return Err(ProgramError::MissingRequiredSignature);
+            }
+            if meta.is_writable && !info.is_writable {
+                return Err(ProgramError::InvalidArgument);
+            }
+            info.is_signer = meta.is_signer;
+            info.is_writable = meta.is_writable;
+            infos.push(info);
+        }
+
+        dispatch(&instruction.program_id, &infos, &instruction.data)
+    }
+}
+
+fn dispatch(program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    CONTEXT.with(|context| {
+        let mut context = context.borrow_mut();
+        context.program_stack.push(*program_id);
+        context.return_data = None;
+    });
+
+    let result = if program_id.eq(&contract::id()) {
+        contract::processor::process_instruction(program_id, accounts, data)
+    } else if program_id.eq(&market_pool::id()) {
+        market_pool::processor::process_instruction(program_id, accounts, data)
+    } else if program_id.eq(&spl_token_2022::id()) {
+        spl_token_2022::processor::Processor::process(program_id, accounts, data)
+    } else if program_id.eq(&spl_associated_token_account::id()) {
+        spl_associated_token_account::processor::process_instruction(program_id, accounts, data)
+    } else if program_id.eq(&system_program::id()) {
+        process_system_instruction(accounts, data)
+    } else {
+        Err(ProgramError::IncorrectProgramId)
+    };
+
+    CONTEXT.with(|context| context.borrow_mut().program_stack.pop());
+    result
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Result<u64, ProgramError> {
+    data.get(offset..offset + 8)
+        .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+        .ok_or(ProgramError::InvalidInstructionData)
+}
+
+fn read_pubkey(data: &[u8], offset: usize) -> Result<Pubkey, ProgramError> {
+    data.get(offset..offset + 32)
+        .map(|bytes| Pubkey::new_from_array(bytes.try_into().unwrap()))
+        .ok_or(ProgramError::InvalidInstructionData)
+}
+
+fn debit(from: &AccountInfo, to: &AccountInfo, lamports: u64) -> ProgramResult {
+    if !from.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if from.owner.ne(&system_program::id()) || from.lamports() < lamports {
+        return Err(ProgramError::InsufficientFunds);
+    }
+    **from.try_borrow_mut_lamports()? -= lamports;
+    **to.try_borrow_mut_lamports()? += lamports;
+    Ok(())
+}
+
+fn allocate(account: &AccountInfo, space: u64) -> ProgramResult {
+    if !account.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if account.owner.ne(&system_program::id()) || account.data_len() != 0 {
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+    account.realloc(space as usize, true)
+}
+
+fn assign(account: &AccountInfo, owner: &Pubkey) -> ProgramResult {
+    if !account.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    if account.owner.ne(&system_program::id()) {
+        return Err(ProgramError::IllegalOwner);
+    }
+    account.assign(owner);
+    Ok(())
+}
+
+// the subset of the system program used by the programs under test
+fn process_system_instruction(accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    let tag = data
+        .get(0..4)
+        .map(|bytes| u32::from_le_bytes(bytes.try_into().unwrap()))
+        .ok_or(ProgramError::InvalidInstructionData)?;
+
+    match tag {
+        // CreateAccount { lamports, space, owner }
+        0 => {
+            let (from, to) = (&accounts[0], &accounts[1]);
+            if to.lamports() > 0 {
+                return Err(ProgramError::AccountAlreadyInitialized);
+            }
+            allocate(to, read_u64(data, 12)?)?;
+            assign(to, &read_pubkey(data, 20)?)?;
+            debit(from, to, read_u64(data, 4)?)
+        }
+        // Assign { owner }
+        1 => assign(&accounts[0], &read_pubkey(data, 4)?),
+        // Transfer { lamports }
+        2 => debit(&accounts[0], &accounts[1], read_u64(data, 4)?),
+        // Allocate { space }
+        8 => allocate(&accounts[0], read_u64(data, 4)?),
+        _ => Err(ProgramError::InvalidInstructionData),
+    }
+}
+
+// lays the account out the way the runtime serializes it: the original data
+// length in front of the key, the current one in front of the data and room
+// behind the data to grow
+fn leak_account_info(
+    key: &Pubkey,
+    lamports: u64,
+    owner: &Pubkey,
+    data: &[u8],
+    executable: bool,
+) -> AccountInfo<'static> {
+    let key_buffer: &'static mut [u8] = Box::leak(vec![0; 4 + 32].into_boxed_slice());
+    key_buffer[..4].copy_from_slice(&(data.len() as u32).to_le_bytes());
+    key_buffer[4..].copy_from_slice(key.as_ref());
+    let key: &'static Pubkey = unsafe { &*(key_buffer.as_ptr().add(4) as *const Pubkey) };
+
+    let data_buffer: &'static mut [u8] =
+        Box::leak(vec![0; 8 + data.len() + MAX_PERMITTED_DATA_INCREASE].into_boxed_slice());
+    data_buffer[..8].copy_from_slice(&(data.len() as u64).to_le_bytes());
+    data_buffer[8..8 + data.len()].copy_from_slice(data);
+    let data: &'static mut [u8] =
+        unsafe { std::slice::from_raw_parts_mut(data_buffer.as_mut_ptr().add(8), data.len()) };
+
+    AccountInfo::new(
+        key,
+        false,
+        false,
+        Box::leak(Box::new(lamports)),
+        data,
+        Box::leak(Box::new(*owner)),
+        executable,
+        0,
+    )
+}
+
+struct Snapshot {
+    lamports: u64,
+    owner: Pubkey,
+    data: Vec<u8>,
+}
+
+pub struct TestRuntime {
+    accounts: HashMap<Pubkey, AccountInfo<'static>>,
+}
+
+impl TestRuntime {
+    pub fn new() -> Self {
+        static INSTALL_STUBS: Once = Once::new();
+        INSTALL_STUBS.call_once(|| {
+            set_syscall_stubs(Box::new(TestSyscallStubs));
+        });
+
+        CONTEXT.with(|context| {
+            let mut context = context.borrow_mut();
+            context.clock = Clock::default();
+            context.logs.clear();
+        });
+
+        let mut runtime = TestRuntime {
+            accounts: HashMap::new(),
+        };
+        for program_id in [
+            contract::id(),
+            market_pool::id(),
+            spl_token_2022::id(),
+            spl_associated_token_account::id(),
+            system_program::id(),
+        ] {
+            let program = leak_account_info(
+                &program_id,
+                1,
+                &solana_program::bpf_loader_upgradeable::id(),
+                &[],
+                true,
+            );
+            runtime.accounts.insert(program_id, program);
+        }
+        runtime
+    }
+
+    pub fn set_time(&self, unix_timestamp: i64) {
+        CONTEXT.with(|context| context.borrow_mut().clock.unix_timestamp = unix_timestamp);
+    }
+
+    pub fn add_account(&mut self, key: &Pubkey, lamports: u64, owner: &Pubkey, data: &[u8]) {
+        let account = leak_account_info(key, lamports, owner, data, false);
+        self.accounts.insert(*key, account);
+    }
+
+    /// A funded wallet.
+    pub fn add_wallet(&mut self) -> Pubkey {
+        let wallet = Pubkey::new_unique();
+        self.add_account(&wallet, 100 * SOL, &system_program::id(), &[]);
+        wallet
+    }
+
+    pub fn add_mint(&mut self, mint_authority: &Pubkey, decimals: u8) -> Pubkey {
+        let mint = Pubkey::new_unique();
+        let mut data = vec![0; Mint::LEN];
+        Mint::pack(
+            Mint {
+                mint_authority: Some(*mint_authority).into(),
+                supply: 0,
+                decimals,
+                is_initialized: true,
+                freeze_authority: None.into(),
+            },
+            &mut data,
+        )
+        .unwrap();
+        let rent = Rent::default().minimum_balance(data.len());
+        self.add_account(&mint, rent, &spl_token_2022::id(), &data);
+        mint
+    }
+
+    /// A mint charging the given basis points on every transfer, without a maximum fee.
+    pub fn add_mint_with_transfer_fee(
+        &mut self,
+        mint_authority: &Pubkey,
+        decimals: u8,
+        transfer_fee_basis_points: u16,
+    ) -> Pubkey {
+        let mint = Pubkey::new_unique();
+        let mut data = vec![
+            0;
+            ExtensionType::try_calculate_account_len::<Mint>(&[
+                ExtensionType::TransferFeeConfig
+            ])
+            .unwrap()
+        ];
+        let mut state = StateWithExtensionsMut::<Mint>::unpack_uninitialized(&mut data).unwrap();
+        let transfer_fee = TransferFee {
+            epoch: 0.into(),
+            maximum_fee: u64::MAX.into(),
+            transfer_fee_basis_points: transfer_fee_basis_points.into(),
+        };
+        let config = state.init_extension::<TransferFeeConfig>(true).unwrap();
+        config.older_transfer_fee = transfer_fee;
+        config.newer_transfer_fee = transfer_fee;
+        state.base = Mint {
+            mint_authority: Some(*mint_authority).into(),
+            supply: 0,
+            decimals,
+            is_initialized: true,
+            freeze_authority: None.into(),
+        };
+        state.pack_base();
+        state.init_account_type().unwrap();
+
+        let rent = Rent::default().minimum_balance(data.len());
+        self.add_account(&mint, rent, &spl_token_2022::id(), &data);
+        mint
+    }
+
+    /// Creates the Token-2022 ATA of the owner holding the amount, with the
+    /// extensions its mint requires. The supply is not tracked.
+    pub fn add_token_account(&mut self, owner: &Pubkey, mint: &Pubkey, amount: u64) -> Pubkey {
+        let ata = get_associated_token_address_with_program_id(owner, mint, &spl_token_2022::id());
+        let mint_data = self.data(mint);
+        let mint_extensions = StateWithExtensions::<Mint>::unpack(&mint_data)
+            .unwrap()
+            .get_extension_types()
+            .unwrap();
+        let extensions = ExtensionType::get_required_init_account_extensions(&mint_extensions);
+
+        let mut data =
+            vec![0; ExtensionType::try_calculate_account_len::<Account>(&extensions).unwrap()];
+        let mut state = StateWithExtensionsMut::<Account>::unpack_uninitialized(&mut data).unwrap();
+        for extension in extensions {
+            match extension {
+                ExtensionType::TransferFeeAmount => {
+                    state.init_extension::<TransferFeeAmount>(true).unwrap();
+                }
+                _ => panic!("unsupported account extension {:?}", extension),
+            }
+        }
+        state.base = Account {
+            mint: *mint,
+            owner: *owner,
+            amount,
+            state: AccountState::Initialized,
+            ..Account::default()
+        };
+        state.pack_base();
+        state.init_account_type().unwrap();
+
+        let rent = Rent::default().minimum_balance(data.len());
+        self.add_account(&ata, rent, &spl_token_2022::id(), &data);
+        ata
+    }
+
+    pub fn exists(&self, key: &Pubkey) -> bool {
+        self.accounts
+            .get(key)
+            .is_some_and(|account| account.lamports() > 0)
+    }
+
+    pub fn lamports(&self, key: &Pubkey) -> u64 {
+        self.accounts
+            .get(key)
+            .map_or(0, |account| account.lamports())
+    }
+
+    pub fn owner(&self, key: &Pubkey) -> Pubkey {
+        *self.accounts[key].owner
+    }
+
+    pub fn data(&self, key: &Pubkey) -> Vec<u8> {
+        self.accounts
+            .get(key)
+            .map_or(vec![], |account| account.data.borrow().to_vec())
+    }
+
+    /// Stores the offering at its PDA and returns the address. Its publisher is
+    /// stored too, counting this offering, unless it exists already.
+    pub fn add_offering(&mut self, offering: &OfferingAccount) -> Pubkey {
+        if !self.exists(&offering.publisher) {
+            let publisher = PublisherAccount {
+                discriminator: PUBLISHER_PDA_TYPE,
+                version: ACCOUNT_VERSION,
+                state: PUBLISHER_IS_INITIALIZED,
+                num_of_offerings: 1,
+                ..PublisherAccount::default()
+            };
+            let mut data = vec![0; PublisherAccount::LEN];
+            PublisherAccount::pack(publisher, &mut data).unwrap();
+            let rent = Rent::default().minimum_balance(data.len());
+            self.add_account(&offering.publisher, rent, &contract::id(), &data);
+        }
+
+        let (offering_pda, _) = find_offering_address(
+            &contract::id(),
+            &offering.publisher,
+            &offering.creator,
+            &offering.offering_seed,
+        );
+        let mut data = vec![0; offering.get_packed_len()];
+        offering.pack(&mut data).unwrap();
+        let rent = Rent::default().minimum_balance(data.len());
+        self.add_account(&offering_pda, rent, &contract::id(), &data);
+        offering_pda
+    }
+
+    pub fn offering(&self, offering_pda: &Pubkey) -> OfferingAccount {
+        OfferingAccount::unpack(&self.data(offering_pda)).unwrap()
+    }
+
+    pub fn publisher(&self, publisher_pda: &Pubkey) -> PublisherAccount {
+        PublisherAccount::unpack(&self.data(publisher_pda)).unwrap()
+    }
+
+    pub fn token_balance(&self, key: &Pubkey) -> u64 {
+        StateWithExtensions::<Account>::unpack(&self.data(key))
+            .unwrap()
+            .base
+            .amount
+    }
+
+    /// Data logged since the last call, one entry per `sol_log_data`.
+    pub fn take_logs(&self) -> Vec<Vec<Vec<u8>>> {
+        CONTEXT.with(|context| std::mem::take(&mut context.borrow_mut().logs))
+    }
+
+    /// Runs the instruction as a transaction signed by the signers of its
+    /// metas, every account change is rolled back when it fails.
+    pub fn process(&mut self, instruction: &Instruction) -> ProgramResult {
+        let mut infos = Vec::with_capacity(instruction.accounts.len());
+        for meta in instruction.accounts.iter() {
+            if !self.accounts.contains_key(&meta.pubkey) {
+                self.add_account(&meta.pubkey, 0, &system_program::id(), &[]);
+            }
+            let mut info = self.accounts[&meta.pubkey].clone();
+            info.is_signer = meta.is_signer;
+            info.is_writable = meta.is_writable;
+            infos.push(info);
+        }
+
+        let snapshots: Vec<(Pubkey, Snapshot)> = infos
+            .iter()
+            .map(|info| {
+                let snapshot = Snapshot {
+                    lamports: info.lamports(),
+                    owner: *info.owner,
+                    data: info.data.borrow().to_vec(),
+                };
+                (*info.key, snapshot)
+            })
+            .collect();
+
+        CONTEXT.with(|context| context.borrow_mut().program_stack.clear());
+        let result = dispatch(&instruction.program_id, &infos, &instruction.data);
+
+        if result.is_err() {
+            for (key, snapshot) in snapshots.into_iter().rev() {
+                let account = &self.accounts[&key];
+                **account.lamports.borrow_mut() = snapshot.lamports;
+                account.assign(&snapshot.owner);
+                account.realloc(snapshot.data.len(), false).unwrap();
+                account.data.borrow_mut().copy_from_slice(&snapshot.data);
+            }
+        }
+
+        result
+    }
+}
@@ -0,0 +1,368 @@
+mod common;
+
+use common::{offering_fixture, TestRuntime};
+use contract::{
+    constant::{
+        ACCOUNT_VERSION, LEGACY_ACCOUNT_VERSION, PUBLISHER_PDA_TYPE, PUBLISHER_TOKEN_PDA_TYPE,
+        PURCHASE_PDA_TYPE,
+    },
+    error::INVALID_EVENT_ERROR,
+    events::*,
+    state::{
+        offering::{OfferingAccount, OFFERING_IS_INITIALIZED},
+        publisher::{PublisherAccount, PUBLISHER_IS_INITIALIZED},
+        publisher_token::{
+            PublisherTokenAccount, ALLOW_TOKEN_AS_FUNDING, PUBLISHER_TOKEN_IS_INITIALIZED,
+        },
+        purchase::{PurchaseAccount, PURCHASE_IS_INITIALIZED},
+    },
+};
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+
+// accounts as they read back once packed, i.e. initialized and at the current version
+fn publisher() -> PublisherAccount {
+    PublisherAccount {
+        discriminator: PUBLISHER_PDA_TYPE,
+        version: ACCOUNT_VERSION,
+        state: PUBLISHER_IS_INITIALIZED,
+        creator: Pubkey::new_unique(),
+        publisher_seed: Pubkey::new_unique(),
+        update_authority: Pubkey::new_unique(),
+        transfer_authority: Pubkey::new_unique(),
+        approval_authority: Pubkey::new_unique(),
+        num_of_publisher_tokens: 3,
+        num_of_offerings: 2,
+    }
+}
+
+fn publisher_token() -> PublisherTokenAccount {
+    PublisherTokenAccount {
+        discriminator: PUBLISHER_TOKEN_PDA_TYPE,
+        version: ACCOUNT_VERSION,
+        state: PUBLISHER_TOKEN_IS_INITIALIZED | ALLOW_TOKEN_AS_FUNDING,
+        publisher: Pubkey::new_unique(),
+        mint: Pubkey::new_unique(),
+        offer_creation_price: 1_000,
+    }
+}
+
+fn offering() -> OfferingAccount {
+    let offering = offering_fixture(&Pubkey::new_unique(), &Pubkey::new_unique());
+    OfferingAccount {
+        state: offering.state | OFFERING_IS_INITIALIZED,
+        metadata_cid: b"bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi".to_vec(),
+        ..offering
+    }
+}
+
+fn purchase() -> PurchaseAccount {
+    PurchaseAccount {
+        discriminator: PURCHASE_PDA_TYPE,
+        version: ACCOUNT_VERSION,
+        state: PURCHASE_IS_INITIALIZED,
+        offering: Pubkey::new_unique(),
+        purchaser: Pubkey::new_unique(),
+        funded_amount: 990,
+        purchased_quantity: 1_980,
+        distributed_quantity: 0,
+        distributed_vestings: 0,
+    }
+}
+
+fn events() -> Vec<ContractEvent> {
+    let key = Pubkey::new_unique;
+    vec![
+        ContractEvent::PublisherInitiated(PublisherInitiatedEvent {
+            timestamp: 1,
+            publisher_pda: key(),
+            publisher: publisher(),
+            payer: key(),
+            authority: key(),
+        }),
+        ContractEvent::PublisherUpdated(PublisherUpdatedEvent {
+            timestamp: 2,
+            publisher_pda: key(),
+            publisher: publisher(),
+            authority: key(),
+        }),
+        ContractEvent::PublisherAuthorityClaimed(PublisherAuthorityClaimedEvent {
+            timestamp: 3,
+            publisher_pda: key(),
+            publisher: publisher(),
+            previous_authority: key(),
+            claimer: key(),
+        }),
+        ContractEvent::PublisherWithdrawal(PublisherWithdrawalEvent {
+            timestamp: 4,
+            publisher_pda: key(),
+            custodial_pda: key(),
+            authority: key(),
+            amount: u64::MAX,
+        }),
+        ContractEvent::PublisherTokenWithdrawal(PublisherTokenWithdrawalEvent {
+            timestamp: 5,
+            publisher_pda: key(),
+            custodial_pda: key(),
+            authority: key(),
+            mint: key(),
+            amount: 42,
+        }),
+        ContractEvent::OfferingInitiated(OfferingInitiatedEvent {
+            timestamp: 6,
+            offering_pda: key(),
+            offering: offering(),
+            payer: key(),
+            promoter: key(),
+            payment_mint: key(),
+            offer_creation_price: 1_000,
+        }),
+        ContractEvent::OfferingApproval(OfferingApprovalEvent {
+            timestamp: 7,
+            offering_pda: key(),
+            offering: offering(),
+            approval_authority: key(),
+            is_approved: false,
+            reason_hash: Some([7; 32]),
+        }),
+        ContractEvent::OfferingApproval(OfferingApprovalEvent {
+            timestamp: 7,
+            offering_pda: key(),
+            offering: offering(),
+            approval_authority: key(),
+            is_approved: true,
+            reason_hash: None,
+        }),
+        ContractEvent::OfferingClosed(OfferingClosedEvent {
+            timestamp: 8,
+            offering_pda: key(),
+            offering: offering(),
+            promoter: key(),
+            withdrawn_amount: 4_000,
+            returned_quantity: 2_000,
+        }),
+        ContractEvent::PurchaseInitiated(PurchaseInitiatedEvent {
+            timestamp: 9,
+            purchase_pda: key(),
+            purchase: purchase(),
+            offering_pda: key(),
+            purchaser: key(),
+            amount: 990,
+        }),
+        ContractEvent::PurchaseAdded(PurchaseAddedEvent {
+            timestamp: 10,
+            purchase_pda: key(),
+            purchase: purchase(),
+            offering_pda: key(),
+            purchaser: key(),
+            amount: 10,
+        }),
+        ContractEvent::PurchaseRefunded(PurchaseRefundedEvent {
+            timestamp: 11,
+            purchase_pda: key(),
+            offering_pda: key(),
+            purchaser: key(),
+            funded_amount: 990,
+        }),
+        ContractEvent::PurchaseDistributionDistributed(PurchaseDistributionDistributedEvent {
+            timestamp: 12,
+            purchase_pda: key(),
+            offering_pda: key(),
+            purchaser: key(),
+            vesting_index: 63,
+            quantity: 1_980,
+        }),
+        ContractEvent::PublisherTokenInitiated(PublisherTokenInitiatedEvent {
+            timestamp: 13,
+            publisher_token_pda: key(),
+            publisher_token: publisher_token(),
+            publisher_pda: key(),
+            authority: key(),
+        }),
+        ContractEvent::PublisherTokenUpdated(PublisherTokenUpdatedEvent {
+            timestamp: 14,
+            publisher_token_pda: key(),
+            publisher_token: publisher_token(),
+            publisher_pda: key(),
+            authority: key(),
+        }),
+        ContractEvent::OfferingCreationFinalized(OfferingCreationFinalizedEvent {
+            timestamp: 15,
+            offering_pda: key(),
+            offering: offering(),
+            promoter: key(),
+            escrow_quantity: 12_000,
+        }),
+        ContractEvent::OfferingTargetUpdated(OfferingTargetUpdatedEvent {
+            timestamp: 16,
+            offering_pda: key(),
+            offering: offering(),
+            promoter: key(),
+        }),
+        ContractEvent::OfferingMarketPoolDeposited(OfferingMarketPoolDepositedEvent {
+            timestamp: 17,
+            offering_pda: key(),
+            offering: offering(),
+            pool_program: key(),
+            funded_amount: 1_000,
+            offered_amount: 2_000,
+            lp_mint: key(),
+            lp_amount: 1_414,
+        }),
+        ContractEvent::AccountMigrated(AccountMigratedEvent {
+            timestamp: 18,
+            pda: key(),
+            previous_version: LEGACY_ACCOUNT_VERSION,
+            version: ACCOUNT_VERSION,
+            publisher: publisher(),
+        }),
+        ContractEvent::PublisherAccountClosed(PublisherAccountClosedEvent {
+            timestamp: 19,
+            publisher_pda: key(),
+            authority: key(),
+            receiver: key(),
+            custodial_balance: 890_880,
+        }),
+        ContractEvent::PublisherTokenAccountClosed(PublisherTokenAccountClosedEvent {
+            timestamp: 20,
+            publisher_token_pda: key(),
+            publisher_pda: key(),
+            mint: key(),
+            receiver: key(),
+        }),
+        ContractEvent::OfferingAccountClosed(OfferingAccountClosedEvent {
+            timestamp: 21,
+            offering_pda: key(),
+            promoter: key(),
+            receiver: key(),
+        }),
+        ContractEvent::PurchaseAccountClosed(PurchaseAccountClosedEvent {
+            timestamp: i64::MIN,
+            purchase_pda: key(),
+            offering_pda: key(),
+            purchaser: key(),
+            receiver: key(),
+        }),
+    ]
+}
+
+fn emit_event(event: &ContractEvent) -> Result<(), ProgramError> {
+    match event {
+        ContractEvent::PublisherInitiated(event) => emit(event),
+        ContractEvent::PublisherUpdated(event) => emit(event),
+        ContractEvent::PublisherAuthorityClaimed(event) => emit(event),
+        ContractEvent::PublisherWithdrawal(event) => emit(event),
+        ContractEvent::PublisherTokenWithdrawal(event) => emit(event),
+        ContractEvent::OfferingInitiated(event) => emit(event),
+        ContractEvent::OfferingApproval(event) => emit(event),
+        ContractEvent::OfferingClosed(event) => emit(event),
+        ContractEvent::PurchaseInitiated(event) => emit(event),
+        ContractEvent::PurchaseAdded(event) => emit(event),
+        ContractEvent::PurchaseRefunded(event) => emit(event),
+        ContractEvent::PurchaseDistributionDistributed(event) => emit(event),
+        ContractEvent::PublisherTokenInitiated(event) => emit(event),
+        ContractEvent::PublisherTokenUpdated(event) => emit(event),
+        ContractEvent::OfferingCreationFinalized(event) => emit(event),
+        ContractEvent::OfferingTargetUpdated(event) => emit(event),
+        ContractEvent::OfferingMarketPoolDeposited(event) => emit(event),
+        ContractEvent::AccountMigrated(event) => emit(event),
+        ContractEvent::PublisherAccountClosed(event) => emit(event),
+        ContractEvent::PublisherTokenAccountClosed(event) => emit(event),
+        ContractEvent::OfferingAccountClosed(event) => emit(event),
+        ContractEvent::PurchaseAccountClosed(event) => emit(event),
+    }
+}
+
+#[test]
+fn test_emit_decode_roundtrip() {
+    let runtime = TestRuntime::new();
+
+    for event in events() {
+        emit_event(&event).unwrap();
+
+        let logs = runtime.take_logs();
+        assert_eq!(logs.len(), 1);
+        assert_eq!(decode_event(&logs[0]), Ok(event));
+    }
+}
+
+#[test]
+fn test_decode_rejects_malformed_events() {
+    let runtime = TestRuntime::new();
+
+    for event in events() {
+        emit_event(&event).unwrap();
+        let data = runtime.take_logs().remove(0);
+
+        let mut trailing = data.clone();
+        trailing.push(vec![0]);
+        assert_eq!(decode_event(&trailing), Err(INVALID_EVENT_ERROR));
+
+        // dropping the optional reason hash leaves a valid approval event
+        if !matches!(event, ContractEvent::OfferingApproval(_)) {
+            assert!(decode_event(&data[..data.len() - 1]).is_err());
+        }
+    }
+
+    assert_eq!(decode_event(&[]), Err(INVALID_EVENT_ERROR));
+    assert_eq!(decode_event(&[vec![0; 8]]), Err(INVALID_EVENT_ERROR));
+}
+
+#[test]
+fn test_decode_rejects_fields_of_the_wrong_length() {
+    let runtime = TestRuntime::new();
+
+    for event in events() {
+        emit_event(&event).unwrap();
+        let data = runtime.take_logs().remove(0);
+
+        for index in 1..data.len() {
+            // an empty reason hash is how a missing one is logged
+            if !data[index].is_empty() {
+                let mut truncated = data.clone();
+                truncated[index].pop();
+                assert!(decode_event(&truncated).is_err(), "{event:?} field {index}");
+            }
+
+            // accounts fail with the length error of their own state
+            let mut extended = data.clone();
+            extended[index].push(0);
+            assert!(decode_event(&extended).is_err(), "{event:?} field {index}");
+        }
+    }
+}
+
+#[test]
+fn test_decode_rejects_unknown_ids_and_values() {
+    let runtime = TestRuntime::new();
+    let event = PurchaseRefundedEvent {
+        timestamp: 11,
+        purchase_pda: Pubkey::new_unique(),
+        offering_pda: Pubkey::new_unique(),
+        purchaser: Pubkey::new_unique(),
+        funded_amount: 990,
+    };
+    emit(&event).unwrap();
+    let data = runtime.take_logs().remove(0);
+
+    let mut unknown = data.clone();
+    unknown[0][0] ^= 0xff;
+    assert_eq!(decode_event(&unknown), Err(INVALID_EVENT_ERROR));
+
+    // the fields of another event do not decode under this ID
+    let mut swapped = data.clone();
+    swapped[0] = PurchaseAccountClosedEvent::ID.to_vec();
+    assert_eq!(decode_event(&swapped), Err(INVALID_EVENT_ERROR));
+
+    let approval = OfferingApprovalEvent {
+        timestamp: 7,
+        offering_pda: Pubkey::new_unique(),
+        offering: offering(),
+        approval_authority: Pubkey::new_unique(),
+        is_approved: true,
+        reason_hash: None,
+    };
+    emit(&approval).unwrap();
+    let mut data = runtime.take_logs().remove(0);
+    data[5] = vec![2];
+    assert_eq!(decode_event(&data), Err(INVALID_EVENT_ERROR));
+}
@@ -0,0 +1,190 @@
+mod common;
+
+use common::{offering_fixture, TestRuntime};
+use contract::{
+    error::{
+        INVALID_MARKET_POOL_LP_MINT_ERROR, INVALID_MARKET_POOL_PROGRAM_ERROR,
+        OFFERING_FUNDS_IS_ALREADY_DEPOSITED_TO_MARKET_POOL_ERROR,
+    },
+    events::{decode_event, ContractEvent, OfferingMarketPoolDepositedEvent},
+    instruction::deposit_market_pool,
+    pda::find_market_pool_address,
+    state::offering::{OfferingAccount, OFFERING_FUNDS_IS_WITHDRAWN},
+};
+use market_pool::instruction::{
+    deposit_accounts, find_lp_mint_address, find_pool_address, initiate_pool,
+};
+use solana_program::{instruction::Instruction, pubkey::Pubkey};
+use spl_associated_token_account_client::address::get_associated_token_address_with_program_id;
+
+struct Fixture {
+    runtime: TestRuntime,
+    promoter: Pubkey,
+    offering_pda: Pubkey,
+    market_pool_pda: Pubkey,
+    funded_mint: Pubkey,
+    offered_mint: Pubkey,
+    lp_mint: Pubkey,
+}
+
+impl Fixture {
+    // a closed offering that raised 2_000, of which 20% go to the market pool
+    fn new() -> Self {
+        let mut runtime = TestRuntime::new();
+        let promoter = runtime.add_wallet();
+        let mint_authority = Pubkey::new_unique();
+        let funded_mint = runtime.add_mint(&mint_authority, 6);
+        let offered_mint = runtime.add_mint(&mint_authority, 6);
+
+        let offering = offering_fixture(&funded_mint, &offered_mint);
+        let offering_pda = runtime.add_offering(&OfferingAccount {
+            state: offering.state | OFFERING_FUNDS_IS_WITHDRAWN,
+            promoter,
+            funded_amount: 2_000,
+            market_pool_percentage: 20,
+            ..offering
+        });
+        runtime.add_token_account(&offering_pda, &funded_mint, 400);
+        runtime.add_token_account(&offering_pda, &offered_mint, 800);
+
+        let (market_pool_pda, _) = find_market_pool_address(&contract::id(), &offering_pda);
+        runtime.add_token_account(&market_pool_pda, &funded_mint, 0);
+        runtime.add_token_account(&market_pool_pda, &offered_mint, 0);
+
+        runtime
+            .process(&initiate_pool(
+                &market_pool::id(),
+                &promoter,
+                &funded_mint,
+                &spl_token_2022::id(),
+                &offered_mint,
+                &spl_token_2022::id(),
+            ))
+            .unwrap();
+        let (pool, _) = find_pool_address(&market_pool::id(), &funded_mint, &offered_mint);
+        let (lp_mint, _) = find_lp_mint_address(&market_pool::id(), &pool);
+        runtime.add_token_account(&market_pool_pda, &lp_mint, 0);
+
+        Fixture {
+            runtime,
+            promoter,
+            offering_pda,
+            market_pool_pda,
+            funded_mint,
+            offered_mint,
+            lp_mint,
+        }
+    }
+
+    fn deposit(&self, lp_mint: &Pubkey, pool_program: &Pubkey) -> Instruction {
+        deposit_market_pool(
+            &contract::id(),
+            &self.promoter,
+            &self.promoter,
+            &self.offering_pda,
+            &self.funded_mint,
+            &spl_token_2022::id(),
+            &self.offered_mint,
+            &spl_token_2022::id(),
+            lp_mint,
+            &spl_token_2022::id(),
+            pool_program,
+            &deposit_accounts(
+                &market_pool::id(),
+                &self.market_pool_pda,
+                &self.funded_mint,
+                &spl_token_2022::id(),
+                &self.offered_mint,
+                &spl_token_2022::id(),
+            ),
+        )
+        .unwrap()
+    }
+
+    fn ata(&self, owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+        get_associated_token_address_with_program_id(owner, mint, &spl_token_2022::id())
+    }
+}
+
+#[test]
+fn test_deposit_market_pool() {
+    let mut fixture = Fixture::new();
+    let deposit = fixture.deposit(&fixture.lp_mint, &market_pool::id());
+    fixture.runtime.process(&deposit).unwrap();
+
+    let (pool, _) = find_pool_address(
+        &market_pool::id(),
+        &fixture.funded_mint,
+        &fixture.offered_mint,
+    );
+    let runtime = &fixture.runtime;
+    assert_eq!(
+        runtime.token_balance(&fixture.ata(&pool, &fixture.funded_mint)),
+        400
+    );
+    assert_eq!(
+        runtime.token_balance(&fixture.ata(&pool, &fixture.offered_mint)),
+        800
+    );
+    assert_eq!(
+        runtime.token_balance(&fixture.ata(&fixture.offering_pda, &fixture.funded_mint)),
+        0
+    );
+    // the first deposit mints isqrt(400 * 800) LP tokens, locked in the market pool PDA
+    let market_pool_lp_ata = fixture.ata(&fixture.market_pool_pda, &fixture.lp_mint);
+    assert_eq!(runtime.token_balance(&market_pool_lp_ata), 565);
+
+    let offering = runtime.offering(&fixture.offering_pda);
+    assert!(offering.is_deposited_to_market_pool());
+    assert_eq!(
+        decode_event(runtime.take_logs().last().unwrap()),
+        Ok(ContractEvent::OfferingMarketPoolDeposited(
+            OfferingMarketPoolDepositedEvent {
+                timestamp: 0,
+                offering_pda: fixture.offering_pda,
+                offering,
+                pool_program: market_pool::id(),
+                funded_amount: 400,
+                offered_amount: 800,
+                lp_mint: fixture.lp_mint,
+                lp_amount: 565,
+            }
+        ))
+    );
+
+    assert_eq!(
+        fixture.runtime.process(&deposit),
+        Err(OFFERING_FUNDS_IS_ALREADY_DEPOSITED_TO_MARKET_POOL_ERROR)
+    );
+}
+
+#[test]
+fn test_deposit_market_pool_rejects_unlisted_pool_program() {
+    let mut fixture = Fixture::new();
+    // executable, but not a pool the contract trusts with the share
+    let deposit = fixture.deposit(&fixture.lp_mint, &spl_token_2022::id());
+    assert_eq!(
+        fixture.runtime.process(&deposit),
+        Err(INVALID_MARKET_POOL_PROGRAM_ERROR)
+    );
+}
+
+#[test]
+fn test_deposit_market_pool_rejects_lp_mint_of_another_pool() {
+    let mut fixture = Fixture::new();
+    // the pool mints its LP tokens, but not to the account the contract watches
+    let other_mint = fixture.runtime.add_mint(&Pubkey::new_unique(), 9);
+    fixture
+        .runtime
+        .add_token_account(&fixture.market_pool_pda, &other_mint, 0);
+
+    let deposit = fixture.deposit(&other_mint, &market_pool::id());
+    assert_eq!(
+        fixture.runtime.process(&deposit),
+        Err(INVALID_MARKET_POOL_LP_MINT_ERROR)
+    );
+    assert!(!fixture
+        .runtime
+        .offering(&fixture.offering_pda)
+        .is_deposited_to_market_pool());
+}
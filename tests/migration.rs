@@ -0,0 +1,215 @@
+mod common;
+
+use common::{offering_fixture, TestRuntime, SOL};
+use contract::{
+    constant::{ACCOUNT_VERSION, LEGACY_ACCOUNT_VERSION, PUBLISHER_PDA_TYPE, PURCHASE_PDA_TYPE},
+    error::{
+        ACCOUNT_IS_ALREADY_MIGRATED_ERROR, ACCOUNT_IS_OUTDATED_ERROR,
+        PUBLISHER_HAS_UNCOUNTED_ACCOUNTS_ERROR,
+    },
+    events::{decode_event, AccountMigratedEvent, ContractEvent},
+    instruction::{
+        claim_publisher_authority, close_publisher_account, initiate_publisher, initiate_purchase,
+        migrate_account, update_publisher,
+    },
+    pda::{find_publisher_address, find_purchase_address},
+    state::{
+        offering::OfferingAccount,
+        publisher::{
+            PublisherAccount, LEGACY_PUBLISHER_LEN, PUBLISHER_HAS_UNCOUNTED_ACCOUNTS,
+            PUBLISHER_IS_INITIALIZED,
+        },
+        purchase::PurchaseAccount,
+    },
+};
+use solana_program::{program_pack::Pack, pubkey::Pubkey, rent::Rent, system_program};
+
+// a publisher as it was packed before the version byte and the token counter were added
+fn add_legacy_publisher(runtime: &mut TestRuntime, creator: &Pubkey) -> (Pubkey, PublisherAccount) {
+    let publisher_seed = Pubkey::new_unique();
+    let (publisher_pda, _) = find_publisher_address(&contract::id(), creator, &publisher_seed);
+    let publisher = PublisherAccount {
+        discriminator: PUBLISHER_PDA_TYPE,
+        version: LEGACY_ACCOUNT_VERSION,
+        state: PUBLISHER_IS_INITIALIZED,
+        creator: *creator,
+        publisher_seed,
+        update_authority: *creator,
+        transfer_authority: *creator,
+        approval_authority: *creator,
+        num_of_publisher_tokens: 0,
+        num_of_offerings: 0,
+    };
+
+    let mut data = vec![0; PublisherAccount::LEN];
+    PublisherAccount::pack(publisher, &mut data).unwrap();
+    data.remove(1);
+    data.truncate(LEGACY_PUBLISHER_LEN);
+
+    let rent = Rent::default().minimum_balance(data.len());
+    runtime.add_account(&publisher_pda, rent, &contract::id(), &data);
+    (publisher_pda, publisher)
+}
+
+#[test]
+fn test_migrate_legacy_publisher_then_load() {
+    let mut runtime = TestRuntime::new();
+    let creator = runtime.add_wallet();
+    let (publisher_pda, legacy_publisher) = add_legacy_publisher(&mut runtime, &creator);
+
+    // writing the legacy layout back is refused until the account is migrated
+    let update = update_publisher(
+        &contract::id(),
+        &creator,
+        &creator,
+        &publisher_pda,
+        None,
+        None,
+        true,
+    )
+    .unwrap();
+    assert_eq!(runtime.process(&update), Err(ACCOUNT_IS_OUTDATED_ERROR));
+    assert_eq!(
+        runtime.process(
+            &claim_publisher_authority(&contract::id(), &creator, &publisher_pda).unwrap()
+        ),
+        Err(ACCOUNT_IS_OUTDATED_ERROR)
+    );
+    assert_eq!(runtime.data(&publisher_pda).len(), LEGACY_PUBLISHER_LEN);
+
+    let payer = runtime.add_wallet();
+    let payer_lamports = runtime.lamports(&payer);
+    runtime
+        .process(&migrate_account(&contract::id(), &payer, &publisher_pda).unwrap())
+        .unwrap();
+
+    let migrated = PublisherAccount::unpack(&runtime.data(&publisher_pda)).unwrap();
+    assert_eq!(
+        migrated,
+        PublisherAccount {
+            version: ACCOUNT_VERSION,
+            state: PUBLISHER_IS_INITIALIZED | PUBLISHER_HAS_UNCOUNTED_ACCOUNTS,
+            ..legacy_publisher
+        }
+    );
+    let rent_exempt = Rent::default().minimum_balance(PublisherAccount::LEN);
+    assert_eq!(runtime.lamports(&publisher_pda), rent_exempt);
+    assert_eq!(
+        payer_lamports - runtime.lamports(&payer),
+        rent_exempt - Rent::default().minimum_balance(LEGACY_PUBLISHER_LEN)
+    );
+
+    let logs = runtime.take_logs();
+    assert_eq!(
+        decode_event(logs.last().unwrap()),
+        Ok(ContractEvent::AccountMigrated(AccountMigratedEvent {
+            timestamp: 0,
+            pda: publisher_pda,
+            previous_version: LEGACY_ACCOUNT_VERSION,
+            version: ACCOUNT_VERSION,
+            publisher: migrated,
+        }))
+    );
+
+    runtime.process(&update).unwrap();
+    let updated = PublisherAccount::unpack(&runtime.data(&publisher_pda)).unwrap();
+    assert!(updated.is_open_for_offering_creation());
+    assert!(updated.has_uncounted_accounts());
+
+    // publisher tokens and offerings from before the migration may still be live
+    runtime
+        .process(
+            &update_publisher(
+                &contract::id(),
+                &creator,
+                &creator,
+                &publisher_pda,
+                None,
+                None,
+                false,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+    assert_eq!(
+        runtime.process(
+            &close_publisher_account(&contract::id(), &creator, &publisher_pda, &creator).unwrap()
+        ),
+        Err(PUBLISHER_HAS_UNCOUNTED_ACCOUNTS_ERROR)
+    );
+
+    assert_eq!(
+        runtime.process(&migrate_account(&contract::id(), &payer, &publisher_pda).unwrap()),
+        Err(ACCOUNT_IS_ALREADY_MIGRATED_ERROR)
+    );
+}
+
+#[test]
+fn test_migrate_rejects_current_accounts() {
+    let mut runtime = TestRuntime::new();
+    let creator = runtime.add_wallet();
+    let publisher_seed = Pubkey::new_unique();
+    let (publisher_pda, _) = find_publisher_address(&contract::id(), &creator, &publisher_seed);
+
+    runtime
+        .process(&initiate_publisher(&contract::id(), &creator, None, &publisher_seed).unwrap())
+        .unwrap();
+    assert_eq!(
+        runtime.process(&migrate_account(&contract::id(), &creator, &publisher_pda).unwrap()),
+        Err(ACCOUNT_IS_ALREADY_MIGRATED_ERROR)
+    );
+
+    // every other account type was created with the version byte
+    let purchase_pda = Pubkey::new_unique();
+    let mut data = vec![0; PurchaseAccount::LEN];
+    PurchaseAccount::pack(
+        PurchaseAccount {
+            discriminator: PURCHASE_PDA_TYPE,
+            ..PurchaseAccount::default()
+        },
+        &mut data,
+    )
+    .unwrap();
+    runtime.add_account(&purchase_pda, 1, &contract::id(), &data);
+    assert_eq!(
+        runtime.process(&migrate_account(&contract::id(), &creator, &purchase_pda).unwrap()),
+        Err(ACCOUNT_IS_ALREADY_MIGRATED_ERROR)
+    );
+}
+
+#[test]
+fn test_accounts_of_another_version_are_not_loaded() {
+    let mut runtime = TestRuntime::new();
+    let purchaser = runtime.add_wallet();
+    let offering = offering_fixture(&system_program::id(), &Pubkey::new_unique());
+    let offering_pda = runtime.add_offering(&offering);
+
+    let purchase = |runtime: &mut TestRuntime| {
+        runtime.process(
+            &initiate_purchase(
+                &contract::id(),
+                &purchaser,
+                None,
+                &offering_pda,
+                &system_program::id(),
+                &system_program::id(),
+                100,
+            )
+            .unwrap(),
+        )
+    };
+    runtime.set_time(25);
+    purchase(&mut runtime).unwrap();
+
+    let (purchase_pda, _) = find_purchase_address(&contract::id(), &offering_pda, &purchaser);
+    let mut data = runtime.data(&purchase_pda);
+    data[1] = ACCOUNT_VERSION + 1;
+    runtime.add_account(&purchase_pda, SOL, &contract::id(), &data);
+    assert_eq!(purchase(&mut runtime), Err(ACCOUNT_IS_OUTDATED_ERROR));
+
+    runtime.add_offering(&OfferingAccount {
+        version: ACCOUNT_VERSION + 1,
+        ..offering
+    });
+    assert_eq!(purchase(&mut runtime), Err(ACCOUNT_IS_OUTDATED_ERROR));
+}
@@ -0,0 +1,183 @@
+mod common;
+
+use common::TestRuntime;
+use contract::{
+    error::OFFERING_OFFERED_MINT_IS_INVALID_ERROR,
+    instruction::{
+        initiate_offering, initiate_publisher, initiate_publisher_token, update_publisher,
+        OfferingTarget,
+    },
+    pda::{find_offering_address, find_publisher_address},
+    state::{
+        offering::OfferingVesting,
+        publisher_token::{
+            ALLOW_TOKEN_AS_FUNDING, ALLOW_TOKEN_AS_OFFERS, ALLOW_TOKEN_AS_OFFER_CREATION_PAYMENT,
+        },
+    },
+};
+use solana_program::{program_error::ProgramError, pubkey::Pubkey, system_program};
+use spl_associated_token_account_client::address::get_associated_token_address_with_program_id;
+
+struct Fixture {
+    runtime: TestRuntime,
+    creator: Pubkey,
+    publisher_pda: Pubkey,
+    offered_mint: Pubkey,
+}
+
+impl Fixture {
+    /// A publisher open for offering creation, accepting SOL as funding and as
+    /// the free offer creation payment, and a Token-2022 mint as offers.
+    fn new() -> Self {
+        let mut runtime = TestRuntime::new();
+        let creator = runtime.add_wallet();
+        let publisher_seed = Pubkey::new_unique();
+        let (publisher_pda, _) = find_publisher_address(&contract::id(), &creator, &publisher_seed);
+        runtime
+            .process(&initiate_publisher(&contract::id(), &creator, None, &publisher_seed).unwrap())
+            .unwrap();
+        runtime
+            .process(
+                &update_publisher(
+                    &contract::id(),
+                    &creator,
+                    &creator,
+                    &publisher_pda,
+                    None,
+                    None,
+                    true,
+                )
+                .unwrap(),
+            )
+            .unwrap();
+
+        let offered_mint = runtime.add_mint(&creator, 6);
+        let mut fixture = Fixture {
+            runtime,
+            creator,
+            publisher_pda,
+            offered_mint,
+        };
+        fixture.add_publisher_token(
+            &system_program::id(),
+            &system_program::id(),
+            ALLOW_TOKEN_AS_FUNDING | ALLOW_TOKEN_AS_OFFER_CREATION_PAYMENT,
+        );
+        fixture.add_publisher_token(&offered_mint, &spl_token_2022::id(), ALLOW_TOKEN_AS_OFFERS);
+        fixture
+    }
+
+    fn add_publisher_token(&mut self, mint: &Pubkey, token_program: &Pubkey, state: u8) {
+        self.runtime
+            .process(
+                &initiate_publisher_token(
+                    &contract::id(),
+                    &self.creator,
+                    None,
+                    &self.publisher_pda,
+                    mint,
+                    token_program,
+                    state,
+                    0,
+                )
+                .unwrap(),
+            )
+            .unwrap();
+    }
+
+    fn initiate_offering(
+        &mut self,
+        funded_mint: &Pubkey,
+        funded_token_program: &Pubkey,
+        offered_mint: &Pubkey,
+    ) -> Result<Pubkey, ProgramError> {
+        let offering_seed = Pubkey::new_unique();
+        let target = OfferingTarget {
+            softcap: 1000,
+            hardcap: 5000,
+            min_funding: 10,
+            max_funding: 2000,
+            publish_time: 10,
+            open_time: 20,
+            close_time: 30,
+            exit_time: 40,
+        };
+        let vestings = vec![OfferingVesting {
+            release_time: 50,
+            percentage: 100,
+        }];
+        let instruction = initiate_offering(
+            &contract::id(),
+            &self.creator,
+            None,
+            &self.publisher_pda,
+            funded_mint,
+            funded_token_program,
+            offered_mint,
+            &system_program::id(),
+            &system_program::id(),
+            &offering_seed,
+            target,
+            10_000,
+            0,
+            vec![],
+            vestings,
+        )?;
+        self.runtime.process(&instruction)?;
+        Ok(find_offering_address(
+            &contract::id(),
+            &self.publisher_pda,
+            &self.creator,
+            &offering_seed,
+        )
+        .0)
+    }
+}
+
+#[test]
+fn test_token_funded_offering_creates_its_funds_ata() {
+    let mut fixture = Fixture::new();
+    let funded_mint = fixture.runtime.add_mint(&fixture.creator, 6);
+    fixture.add_publisher_token(&funded_mint, &spl_token_2022::id(), ALLOW_TOKEN_AS_FUNDING);
+
+    let offered_mint = fixture.offered_mint;
+    let offering_pda = fixture
+        .initiate_offering(&funded_mint, &spl_token_2022::id(), &offered_mint)
+        .unwrap();
+
+    let funds_ata = get_associated_token_address_with_program_id(
+        &offering_pda,
+        &funded_mint,
+        &spl_token_2022::id(),
+    );
+    assert!(fixture.runtime.exists(&funds_ata));
+    assert_eq!(fixture.runtime.owner(&funds_ata), spl_token_2022::id());
+    assert_eq!(fixture.runtime.token_balance(&funds_ata), 0);
+}
+
+#[test]
+fn test_sol_funded_offering_passes_no_funds_accounts() {
+    let mut fixture = Fixture::new();
+    let offered_mint = fixture.offered_mint;
+    let offering_pda = fixture
+        .initiate_offering(&system_program::id(), &system_program::id(), &offered_mint)
+        .unwrap();
+    assert!(fixture.runtime.exists(&offering_pda));
+    assert_eq!(
+        fixture
+            .runtime
+            .publisher(&fixture.publisher_pda)
+            .num_of_offerings,
+        1
+    );
+}
+
+#[test]
+fn test_offering_native_sol_is_rejected() {
+    let mut fixture = Fixture::new();
+    let offered_mint = fixture.offered_mint;
+    assert_eq!(
+        fixture.initiate_offering(&offered_mint, &spl_token_2022::id(), &system_program::id()),
+        Err(OFFERING_OFFERED_MINT_IS_INVALID_ERROR)
+    );
+}
@@ -0,0 +1,88 @@
+// There is no SBF runtime in these tests, so compute units can not be metered.
+// Heap allocations are used as the proxy instead: on chain every allocation
+// and the copy that fills it is paid in compute units, and a full unpack of an
+// offering allocates its metadata_cid and vestings on every purchase.
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+use contract::state::{
+    offering::{
+        OfferingAccount, OfferingAccountView, OfferingVesting, MAX_NUM_OF_VESTINGS,
+        OFFERING_IS_APPROVED_BY_PUBLISHER, OFFERING_OFFER_IS_PROVIDED,
+    },
+    variable_len_pack::VariableLenPack,
+};
+use solana_program::pubkey::Pubkey;
+
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATED_BYTES: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATED_BYTES.try_with(|bytes| bytes.set(bytes.get() + layout.size()));
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn allocated_bytes<F: FnOnce()>(f: F) -> usize {
+    let before = ALLOCATED_BYTES.with(Cell::get);
+    f();
+    ALLOCATED_BYTES.with(Cell::get) - before
+}
+
+// the reads and writes initiate_purchase does on the offering
+fn purchase_with_view(data: &mut [u8], amount: u64) {
+    let mut view = OfferingAccountView::new(data).unwrap();
+    assert!(view.is_purchasable(25) && view.is_approved() && view.is_offer_provided());
+    assert!(view.funded_mint().ne(&Pubkey::default()));
+    view.set_funded_amount(view.funded_amount() + amount);
+    view.set_num_of_purchasers(view.num_of_purchasers() + 1);
+    view.set_num_of_open_purchases(view.num_of_open_purchases() + 1);
+}
+
+fn purchase_with_unpack(data: &mut [u8], amount: u64) {
+    let mut offering = OfferingAccount::unpack(data).unwrap();
+    assert!(offering.is_purchasable(25) && offering.is_approved() && offering.is_offer_provided());
+    assert!(offering.funded_mint.ne(&Pubkey::default()));
+    offering.funded_amount += amount;
+    offering.num_of_purchasers += 1;
+    offering.num_of_open_purchases += 1;
+    offering.pack(data).unwrap();
+}
+
+#[test]
+fn test_view_purchase_path_does_not_allocate() {
+    let offering = OfferingAccount {
+        state: OFFERING_IS_APPROVED_BY_PUBLISHER | OFFERING_OFFER_IS_PROVIDED,
+        funded_mint: Pubkey::new_unique(),
+        hardcap: 5_000,
+        open_time: 20,
+        close_time: 30,
+        metadata_cid: vec![1; 59],
+        vestings: vec![OfferingVesting::default(); MAX_NUM_OF_VESTINGS],
+        ..OfferingAccount::default()
+    };
+    let mut data = vec![0; offering.get_packed_len()];
+    offering.pack(&mut data).unwrap();
+    let mut view_data = data.clone();
+
+    let view_bytes = allocated_bytes(|| purchase_with_view(&mut view_data, 100));
+    let unpack_bytes = allocated_bytes(|| purchase_with_unpack(&mut data, 100));
+
+    // both paths leave the account in the same state
+    assert_eq!(view_data, data);
+    assert_eq!(view_bytes, 0);
+    assert!(unpack_bytes >= 59 + MAX_NUM_OF_VESTINGS * 9);
+}
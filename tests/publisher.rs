@@ -0,0 +1,77 @@
+mod common;
+
+use common::TestRuntime;
+use contract::{
+    instruction::{initiate_publisher, update_publisher},
+    pda::find_publisher_address,
+    state::publisher::PublisherAccount,
+};
+use solana_program::{program_pack::Pack, pubkey::Pubkey};
+
+#[test]
+fn test_update_publisher_only_overwrites_the_authorities_passed() {
+    let mut runtime = TestRuntime::new();
+    let creator = runtime.add_wallet();
+    let publisher_seed = Pubkey::new_unique();
+    let (publisher_pda, _) = find_publisher_address(&contract::id(), &creator, &publisher_seed);
+    runtime
+        .process(&initiate_publisher(&contract::id(), &creator, None, &publisher_seed).unwrap())
+        .unwrap();
+
+    let transfer_authority = Pubkey::new_unique();
+    let approval_authority = Pubkey::new_unique();
+    let update = |transfer_authority, approval_authority, is_open_for_offering_creation| {
+        update_publisher(
+            &contract::id(),
+            &creator,
+            &creator,
+            &publisher_pda,
+            transfer_authority,
+            approval_authority,
+            is_open_for_offering_creation,
+        )
+        .unwrap()
+    };
+    let publisher =
+        |runtime: &TestRuntime| PublisherAccount::unpack(&runtime.data(&publisher_pda)).unwrap();
+
+    runtime
+        .process(&update(
+            Some(transfer_authority),
+            Some(approval_authority),
+            false,
+        ))
+        .unwrap();
+    assert_eq!(publisher(&runtime).transfer_authority, transfer_authority);
+    assert_eq!(publisher(&runtime).approval_authority, approval_authority);
+
+    // toggling the offering creation alone passes no authority
+    let toggle = update(None, None, true);
+    assert_eq!(toggle.accounts.len(), 2);
+    runtime.process(&toggle).unwrap();
+    let updated = publisher(&runtime);
+    assert!(updated.is_open_for_offering_creation());
+    assert_eq!(updated.transfer_authority, transfer_authority);
+    assert_eq!(updated.approval_authority, approval_authority);
+
+    // cancelling the nomination keeps the approval authority
+    runtime.process(&update(Some(creator), None, true)).unwrap();
+    let updated = publisher(&runtime);
+    assert_eq!(updated.transfer_authority, creator);
+    assert_eq!(updated.approval_authority, approval_authority);
+
+    // the approval authority alone, then both set to the same key
+    runtime
+        .process(&update(None, Some(transfer_authority), true))
+        .unwrap();
+    let updated = publisher(&runtime);
+    assert_eq!(updated.transfer_authority, creator);
+    assert_eq!(updated.approval_authority, transfer_authority);
+
+    let same = update(Some(approval_authority), Some(approval_authority), true);
+    assert_eq!(same.accounts.len(), 3);
+    runtime.process(&same).unwrap();
+    let updated = publisher(&runtime);
+    assert_eq!(updated.transfer_authority, approval_authority);
+    assert_eq!(updated.approval_authority, approval_authority);
+}
@@ -0,0 +1,239 @@
+mod common;
+
+use common::TestRuntime;
+use contract::{
+    error::{
+        INVALID_OFFER_CREATION_PRICE_ERROR, PUBLISHER_HAS_PUBLISHER_TOKENS_ERROR,
+        PUBLISHER_TOKEN_CUSTODIAL_BALANCE_IS_NOT_EMPTY_ERROR,
+    },
+    instruction::{
+        close_publisher_account, close_publisher_token_account, initiate_publisher,
+        initiate_publisher_token, update_publisher_token, withdraw_publisher_token,
+    },
+    pda::{find_custodial_address, find_publisher_address, find_publisher_token_address},
+    state::{
+        publisher::PublisherAccount,
+        publisher_token::{
+            PublisherTokenAccount, ALLOW_TOKEN_AS_OFFER_CREATION_PAYMENT,
+            PUBLISHER_TOKEN_IS_DISABLED,
+        },
+    },
+};
+use solana_program::{program_pack::Pack, pubkey::Pubkey, rent::Rent, system_program};
+use spl_associated_token_account_client::address::get_associated_token_address_with_program_id;
+
+fn add_publisher(runtime: &mut TestRuntime, creator: &Pubkey) -> Pubkey {
+    let publisher_seed = Pubkey::new_unique();
+    runtime
+        .process(&initiate_publisher(&contract::id(), creator, None, &publisher_seed).unwrap())
+        .unwrap();
+    find_publisher_address(&contract::id(), creator, &publisher_seed).0
+}
+
+#[test]
+fn test_sol_offer_creation_price_below_rent_minimum_is_rejected() {
+    let mut runtime = TestRuntime::new();
+    let creator = runtime.add_wallet();
+    let publisher_pda = add_publisher(&mut runtime, &creator);
+    let rent_minimum = Rent::default().minimum_balance(0);
+
+    let init = |price| {
+        initiate_publisher_token(
+            &contract::id(),
+            &creator,
+            None,
+            &publisher_pda,
+            &system_program::id(),
+            &system_program::id(),
+            ALLOW_TOKEN_AS_OFFER_CREATION_PAYMENT,
+            price,
+        )
+        .unwrap()
+    };
+    assert_eq!(
+        runtime.process(&init(rent_minimum - 1)),
+        Err(INVALID_OFFER_CREATION_PRICE_ERROR)
+    );
+    runtime.process(&init(rent_minimum)).unwrap();
+
+    let update = |price| {
+        update_publisher_token(
+            &contract::id(),
+            &creator,
+            &publisher_pda,
+            &system_program::id(),
+            ALLOW_TOKEN_AS_OFFER_CREATION_PAYMENT,
+            price,
+        )
+        .unwrap()
+    };
+    assert_eq!(
+        runtime.process(&update(1)),
+        Err(INVALID_OFFER_CREATION_PRICE_ERROR)
+    );
+    // a free offer creation never pays into the custodial account
+    runtime.process(&update(0)).unwrap();
+
+    let (publisher_token_pda, _) =
+        find_publisher_token_address(&contract::id(), &publisher_pda, &system_program::id());
+    let publisher_token =
+        PublisherTokenAccount::unpack(&runtime.data(&publisher_token_pda)).unwrap();
+    assert_eq!(publisher_token.offer_creation_price, 0);
+}
+
+#[test]
+fn test_token_offer_creation_price_has_no_minimum() {
+    let mut runtime = TestRuntime::new();
+    let creator = runtime.add_wallet();
+    let publisher_pda = add_publisher(&mut runtime, &creator);
+    let mint = runtime.add_mint(&creator, 6);
+
+    runtime
+        .process(
+            &initiate_publisher_token(
+                &contract::id(),
+                &creator,
+                None,
+                &publisher_pda,
+                &mint,
+                &spl_token_2022::id(),
+                ALLOW_TOKEN_AS_OFFER_CREATION_PAYMENT,
+                1,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+}
+
+#[test]
+fn test_publisher_is_closed_only_without_publisher_tokens() {
+    let mut runtime = TestRuntime::new();
+    let creator = runtime.add_wallet();
+    let publisher_pda = add_publisher(&mut runtime, &creator);
+    let mint = runtime.add_mint(&creator, 6);
+
+    for (mint, token_program) in [
+        (system_program::id(), system_program::id()),
+        (mint, spl_token_2022::id()),
+    ] {
+        runtime
+            .process(
+                &initiate_publisher_token(
+                    &contract::id(),
+                    &creator,
+                    None,
+                    &publisher_pda,
+                    &mint,
+                    &token_program,
+                    PUBLISHER_TOKEN_IS_DISABLED,
+                    0,
+                )
+                .unwrap(),
+            )
+            .unwrap();
+    }
+    let publisher = PublisherAccount::unpack(&runtime.data(&publisher_pda)).unwrap();
+    assert_eq!(publisher.num_of_publisher_tokens, 2);
+
+    // the SPL token gets an empty custodial ATA, native SOL is held by the custodial PDA itself
+    let (custodial_pda, _) = find_custodial_address(&contract::id(), &publisher_pda);
+    let custodial_ata =
+        get_associated_token_address_with_program_id(&custodial_pda, &mint, &spl_token_2022::id());
+    assert_eq!(runtime.owner(&custodial_ata), spl_token_2022::id());
+    assert_eq!(runtime.token_balance(&custodial_ata), 0);
+
+    let close_publisher =
+        close_publisher_account(&contract::id(), &creator, &publisher_pda, &creator).unwrap();
+    assert_eq!(
+        runtime.process(&close_publisher),
+        Err(PUBLISHER_HAS_PUBLISHER_TOKENS_ERROR)
+    );
+
+    // no revenue was ever paid in the SPL token, so its custodial ATA is empty
+    for (mint, token_program) in [
+        (system_program::id(), system_program::id()),
+        (mint, spl_token_2022::id()),
+    ] {
+        runtime
+            .process(
+                &close_publisher_token_account(
+                    &contract::id(),
+                    &creator,
+                    &publisher_pda,
+                    &mint,
+                    &token_program,
+                    &creator,
+                )
+                .unwrap(),
+            )
+            .unwrap();
+    }
+    let publisher = PublisherAccount::unpack(&runtime.data(&publisher_pda)).unwrap();
+    assert_eq!(publisher.num_of_publisher_tokens, 0);
+
+    runtime.process(&close_publisher).unwrap();
+    assert!(!runtime.exists(&publisher_pda));
+}
+
+#[test]
+fn test_publisher_token_is_closed_only_with_an_empty_custodial_ata() {
+    let mut runtime = TestRuntime::new();
+    let creator = runtime.add_wallet();
+    let publisher_pda = add_publisher(&mut runtime, &creator);
+    let mint = runtime.add_mint(&creator, 6);
+    let (custodial_pda, _) = find_custodial_address(&contract::id(), &publisher_pda);
+    // creating the custodial ATA is idempotent, so one funded beforehand is kept
+    let custodial_ata = runtime.add_token_account(&custodial_pda, &mint, 500);
+    runtime.add_token_account(&creator, &mint, 0);
+
+    runtime
+        .process(
+            &initiate_publisher_token(
+                &contract::id(),
+                &creator,
+                None,
+                &publisher_pda,
+                &mint,
+                &spl_token_2022::id(),
+                PUBLISHER_TOKEN_IS_DISABLED,
+                0,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+    assert_eq!(runtime.token_balance(&custodial_ata), 500);
+
+    let close = close_publisher_token_account(
+        &contract::id(),
+        &creator,
+        &publisher_pda,
+        &mint,
+        &spl_token_2022::id(),
+        &creator,
+    )
+    .unwrap();
+    assert_eq!(
+        runtime.process(&close),
+        Err(PUBLISHER_TOKEN_CUSTODIAL_BALANCE_IS_NOT_EMPTY_ERROR)
+    );
+
+    runtime
+        .process(
+            &withdraw_publisher_token(
+                &contract::id(),
+                &creator,
+                &publisher_pda,
+                &mint,
+                &spl_token_2022::id(),
+                0,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+    assert_eq!(runtime.token_balance(&custodial_ata), 0);
+
+    runtime.process(&close).unwrap();
+    let (publisher_token_pda, _) =
+        find_publisher_token_address(&contract::id(), &publisher_pda, &mint);
+    assert!(!runtime.exists(&publisher_token_pda));
+}
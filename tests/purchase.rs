@@ -0,0 +1,322 @@
+mod common;
+
+use common::{offering_fixture, TestRuntime, SOL};
+use contract::{
+    constant::{ACCOUNT_VERSION, PURCHASE_PDA_TYPE},
+    error::{
+        OFFERING_HAS_PURCHASES_ERROR, OFFERING_IS_NOT_DISTRIBUTED_ERROR,
+        PURCHASE_DISTRIBUTION_IS_DISTRIBUTED_ERROR,
+    },
+    instruction::{
+        close_offering_account, close_purchase_account, distribute, initiate_purchase,
+        refund_purchase,
+    },
+    pda::find_purchase_address,
+    state::{
+        offering::OFFERING_FUNDS_IS_WITHDRAWN,
+        purchase::{PurchaseAccount, PURCHASE_IS_INITIALIZED},
+    },
+};
+use solana_program::{program_pack::Pack, pubkey::Pubkey, system_program};
+
+#[test]
+fn test_purchase_is_credited_with_the_amount_received() {
+    let mut runtime = TestRuntime::new();
+    let mint_authority = Pubkey::new_unique();
+    // 1% of every transfer is withheld by the mint
+    let funded_mint = runtime.add_mint_with_transfer_fee(&mint_authority, 6, 100);
+    let offering_pda = runtime.add_offering(&offering_fixture(&funded_mint, &Pubkey::new_unique()));
+    let offering_funds_ata = runtime.add_token_account(&offering_pda, &funded_mint, 0);
+    let purchaser = runtime.add_wallet();
+    runtime.add_token_account(&purchaser, &funded_mint, 10_000);
+
+    runtime.set_time(25);
+    runtime
+        .process(
+            &initiate_purchase(
+                &contract::id(),
+                &purchaser,
+                None,
+                &offering_pda,
+                &funded_mint,
+                &spl_token_2022::id(),
+                1_000,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+    assert_eq!(runtime.token_balance(&offering_funds_ata), 990);
+    assert_eq!(runtime.offering(&offering_pda).funded_amount, 990);
+
+    let (purchase_pda, _) = find_purchase_address(&contract::id(), &offering_pda, &purchaser);
+    let purchase = PurchaseAccount::unpack(&runtime.data(&purchase_pda)).unwrap();
+    assert_eq!(purchase.funded_amount, 990);
+    // 10_000 offered tokens for a hardcap of 5_000
+    assert_eq!(purchase.purchased_quantity, 1_980);
+}
+
+#[test]
+fn test_refund_keeps_num_of_purchasers() {
+    let mut runtime = TestRuntime::new();
+    let offering_pda = runtime.add_offering(&offering_fixture(
+        &system_program::id(),
+        &Pubkey::new_unique(),
+    ));
+    let first = runtime.add_wallet();
+    let second = runtime.add_wallet();
+
+    runtime.set_time(25);
+    for purchaser in [first, second] {
+        runtime
+            .process(
+                &initiate_purchase(
+                    &contract::id(),
+                    &purchaser,
+                    None,
+                    &offering_pda,
+                    &system_program::id(),
+                    &system_program::id(),
+                    100,
+                )
+                .unwrap(),
+            )
+            .unwrap();
+    }
+
+    let offering = runtime.offering(&offering_pda);
+    assert_eq!(offering.funded_amount, 200);
+    assert_eq!(offering.num_of_purchasers, 2);
+    assert_eq!(offering.num_of_open_purchases, 2);
+
+    // below the softcap once closed, every purchase can be refunded
+    runtime.set_time(35);
+    let first_lamports = runtime.lamports(&first);
+    runtime
+        .process(
+            &refund_purchase(
+                &contract::id(),
+                &first,
+                None,
+                &offering_pda,
+                &system_program::id(),
+                &system_program::id(),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+    let (purchase_pda, _) = find_purchase_address(&contract::id(), &offering_pda, &first);
+    assert!(!runtime.exists(&purchase_pda));
+    assert!(runtime.lamports(&first) > first_lamports + 100);
+
+    let offering = runtime.offering(&offering_pda);
+    assert_eq!(offering.num_of_purchasers, 2);
+    assert_eq!(offering.num_of_open_purchases, 1);
+}
+
+// stands in for CLOSE_OFFERING, which settles the funds of a finished offering
+fn withdraw_offering_funds(runtime: &mut TestRuntime, offering_pda: &Pubkey) {
+    let mut offering = runtime.offering(offering_pda);
+    offering.state |= OFFERING_FUNDS_IS_WITHDRAWN;
+    runtime.add_offering(&offering);
+}
+
+#[test]
+fn test_offering_is_closed_once_distributed_and_its_purchases_closed() {
+    let mut runtime = TestRuntime::new();
+    let offered_mint = runtime.add_mint(&Pubkey::new_unique(), 0);
+    let offering_pda =
+        runtime.add_offering(&offering_fixture(&system_program::id(), &offered_mint));
+    runtime.add_token_account(&offering_pda, &offered_mint, 10_000);
+    let promoter = runtime.offering(&offering_pda).promoter;
+    let purchasers = [runtime.add_wallet(), runtime.add_wallet()];
+
+    runtime.set_time(25);
+    for purchaser in purchasers {
+        runtime.add_token_account(&purchaser, &offered_mint, 0);
+        runtime
+            .process(
+                &initiate_purchase(
+                    &contract::id(),
+                    &purchaser,
+                    None,
+                    &offering_pda,
+                    &system_program::id(),
+                    &system_program::id(),
+                    600,
+                )
+                .unwrap(),
+            )
+            .unwrap();
+    }
+
+    runtime.set_time(35);
+    withdraw_offering_funds(&mut runtime, &offering_pda);
+    let publisher_pda = runtime.offering(&offering_pda).publisher;
+    let close_offering = close_offering_account(
+        &contract::id(),
+        &promoter,
+        &publisher_pda,
+        &offering_pda,
+        &promoter,
+    )
+    .unwrap();
+    assert_eq!(
+        runtime.process(&close_offering),
+        Err(OFFERING_IS_NOT_DISTRIBUTED_ERROR)
+    );
+
+    // anybody cranks the distributions, the purchasers never sign
+    let cranker = runtime.add_wallet();
+    runtime.set_time(60);
+    for (index, purchaser) in purchasers.into_iter().enumerate() {
+        if index > 0 {
+            assert_eq!(
+                runtime.process(&close_offering),
+                Err(OFFERING_IS_NOT_DISTRIBUTED_ERROR)
+            );
+        }
+        runtime
+            .process(
+                &distribute(
+                    &contract::id(),
+                    &cranker,
+                    Some(purchaser),
+                    &offering_pda,
+                    &offered_mint,
+                    &spl_token_2022::id(),
+                )
+                .unwrap(),
+            )
+            .unwrap();
+    }
+
+    let offering = runtime.offering(&offering_pda);
+    assert_eq!(offering.distributed_quantity, 2_400);
+    assert_eq!(offering.num_of_open_purchases, 2);
+
+    // a new offering with the same seed would get the same purchase PDAs back
+    for purchaser in purchasers {
+        assert_eq!(
+            runtime.process(&close_offering),
+            Err(OFFERING_HAS_PURCHASES_ERROR)
+        );
+        let (purchase_pda, _) = find_purchase_address(&contract::id(), &offering_pda, &purchaser);
+        runtime
+            .process(
+                &close_purchase_account(&contract::id(), &purchaser, &offering_pda, &purchaser)
+                    .unwrap(),
+            )
+            .unwrap();
+        assert!(!runtime.exists(&purchase_pda));
+    }
+
+    assert_eq!(runtime.publisher(&publisher_pda).num_of_offerings, 1);
+    runtime.process(&close_offering).unwrap();
+    assert!(!runtime.exists(&offering_pda));
+    assert_eq!(runtime.publisher(&publisher_pda).num_of_offerings, 0);
+}
+
+#[test]
+fn test_distributed_purchase_is_not_topped_up() {
+    let mut runtime = TestRuntime::new();
+    let offering_pda = runtime.add_offering(&offering_fixture(
+        &system_program::id(),
+        &Pubkey::new_unique(),
+    ));
+    let purchaser = runtime.add_wallet();
+
+    // a purchase left behind with its tokens already distributed
+    let (purchase_pda, _) = find_purchase_address(&contract::id(), &offering_pda, &purchaser);
+    let purchase = PurchaseAccount {
+        discriminator: PURCHASE_PDA_TYPE,
+        version: ACCOUNT_VERSION,
+        state: PURCHASE_IS_INITIALIZED,
+        offering: offering_pda,
+        purchaser,
+        funded_amount: 100,
+        purchased_quantity: 200,
+        distributed_quantity: 200,
+        distributed_vestings: 1,
+    };
+    let mut data = vec![0; PurchaseAccount::LEN];
+    PurchaseAccount::pack(purchase, &mut data).unwrap();
+    runtime.add_account(&purchase_pda, SOL, &contract::id(), &data);
+
+    runtime.set_time(25);
+    assert_eq!(
+        runtime.process(
+            &initiate_purchase(
+                &contract::id(),
+                &purchaser,
+                None,
+                &offering_pda,
+                &system_program::id(),
+                &system_program::id(),
+                100,
+            )
+            .unwrap(),
+        ),
+        Err(PURCHASE_DISTRIBUTION_IS_DISTRIBUTED_ERROR)
+    );
+}
+
+#[test]
+fn test_failed_offering_is_closed_once_refunded() {
+    let mut runtime = TestRuntime::new();
+    let offering_pda = runtime.add_offering(&offering_fixture(
+        &system_program::id(),
+        &Pubkey::new_unique(),
+    ));
+    let promoter = runtime.offering(&offering_pda).promoter;
+    let purchaser = runtime.add_wallet();
+
+    runtime.set_time(25);
+    runtime
+        .process(
+            &initiate_purchase(
+                &contract::id(),
+                &purchaser,
+                None,
+                &offering_pda,
+                &system_program::id(),
+                &system_program::id(),
+                100,
+            )
+            .unwrap(),
+        )
+        .unwrap();
+
+    runtime.set_time(35);
+    withdraw_offering_funds(&mut runtime, &offering_pda);
+    let publisher_pda = runtime.offering(&offering_pda).publisher;
+    let close_offering = close_offering_account(
+        &contract::id(),
+        &promoter,
+        &publisher_pda,
+        &offering_pda,
+        &promoter,
+    )
+    .unwrap();
+    assert_eq!(
+        runtime.process(&close_offering),
+        Err(OFFERING_HAS_PURCHASES_ERROR)
+    );
+
+    runtime
+        .process(
+            &refund_purchase(
+                &contract::id(),
+                &purchaser,
+                None,
+                &offering_pda,
+                &system_program::id(),
+                &system_program::id(),
+            )
+            .unwrap(),
+        )
+        .unwrap();
+    runtime.process(&close_offering).unwrap();
+    assert!(!runtime.exists(&offering_pda));
+}